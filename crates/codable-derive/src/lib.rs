@@ -1,2 +1,2 @@
 #[doc(inline)]
-pub use codable_macro::{Decode, Encode};
+pub use codable_macro::{Decode, Encode, ToCodingKey};
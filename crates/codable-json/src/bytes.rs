@@ -0,0 +1,162 @@
+use std::fmt::Write as _;
+
+use codable::{
+    dec::{CustomError, Decode, ErrorCollector},
+    CodingPath,
+};
+
+use crate::{dec::Error, options::BytesFormat, JsonDecoder, JsonOptions, Value};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn to_value(format: BytesFormat, bytes: &[u8]) -> Value {
+    match format {
+        BytesFormat::Base64 => Value::String(encode_base64(bytes)),
+        BytesFormat::Hex => Value::String(encode_hex(bytes)),
+        BytesFormat::Array => {
+            Value::Array(bytes.iter().map(|x| Value::Number(x.to_string())).collect())
+        }
+    }
+}
+
+/// Decodes a byte string in the format of `options`. An array is decoded like
+/// any other sequence, recording its errors in `errors` when they are being
+/// collected.
+pub(crate) fn from_value(
+    options: &std::sync::Arc<JsonOptions>,
+    errors: &Option<ErrorCollector<Error>>,
+    coding_path: &CodingPath<'_>,
+    value: &Value,
+) -> Result<Vec<u8>, Error> {
+    let (decoded, expected) = match (options.bytes, value) {
+        (BytesFormat::Array, _) => {
+            return Vec::<u8>::decode(&mut JsonDecoder::new(
+                coding_path.clone(),
+                value,
+                options.clone(),
+                errors.clone(),
+            ))
+        }
+        (BytesFormat::Base64, Value::String(x)) => (decode_base64(x), "a base64 string"),
+        (BytesFormat::Hex, Value::String(x)) => (decode_hex(x), "a hex string"),
+        (_, other) => {
+            return Err(Error::invalid_type(
+                coding_path.to_path_buf(),
+                "string",
+                other.kind(),
+            ))
+        }
+    };
+
+    decoded.ok_or_else(|| Error::invalid_type(coding_path.to_path_buf(), expected, "string"))
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    fn sextet(c: u8) -> Option<u32> {
+        let x = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        Some(u32::from(x))
+    }
+
+    let input = input.as_bytes();
+    if !input.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    let last = input.len() / 4;
+    for (i, chunk) in input.chunks(4).enumerate() {
+        let pad = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if pad > 2 || (pad > 0 && i + 1 != last) {
+            return None;
+        }
+
+        let mut n = 0;
+        for &c in &chunk[..4 - pad] {
+            n = n << 6 | sextet(c)?;
+        }
+        n <<= 6 * pad;
+        out.extend_from_slice(&n.to_be_bytes()[1..4 - pad]);
+    }
+    Some(out)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{b:02x}");
+    }
+    out
+}
+
+fn decode_hex(input: &str) -> Option<Vec<u8>> {
+    if !input.len().is_multiple_of(2) {
+        return None;
+    }
+
+    input
+        .as_bytes()
+        .chunks(2)
+        .map(|x| {
+            let hi = char::from(x[0]).to_digit(16)?;
+            let lo = char::from(x[1]).to_digit(16)?;
+            Some((hi << 4 | lo) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn base64_round_trip() {
+        for (raw, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"\xff\x00\xfe", "/wD+"),
+        ] {
+            assert_eq!(encode_base64(raw), encoded);
+            assert_eq!(decode_base64(encoded).as_deref(), Some(raw));
+        }
+
+        assert_eq!(decode_base64("Zg="), None);
+        assert_eq!(decode_base64("Zg==Zg=="), None);
+        assert_eq!(decode_base64("Z!=="), None);
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(encode_hex(b"\x00\xabz"), "00ab7a");
+        assert_eq!(decode_hex("00AB7a").as_deref(), Some(&b"\x00\xabz"[..]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
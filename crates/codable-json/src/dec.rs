@@ -1,80 +1,317 @@
-use std::str::FromStr;
+use std::{borrow::Cow, str::FromStr, sync::Arc};
 
 use indexmap::IndexMap;
 
 use codable::{
-    dec::{self, CustomError, Decode, Decoder},
-    CodingKey, CodingPath, ToCodingKey,
+    dec::{self, CustomError, Decode, Decoder, ErrorCollector, Limit, Limits, ValueKind},
+    CodingKey, CodingPath, CodingPathBuf, ToCodingKey, UserInfo,
 };
 
-use crate::Value;
+#[cfg(feature = "tracing")]
+use tracing_error::SpanTrace;
+
+use crate::{bytes, BytesFormat, JsonOptions, Value};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("Key not found")]
-    KeyNotFound(String),
-    #[error("Invalid type")]
-    InvalidType(String),
-    #[error("{0}")]
-    Custom(String, String),
+    #[error("Missing key `{key}` at {coding_path}")]
+    KeyNotFound {
+        coding_path: CodingPathBuf,
+        key: String,
+        #[cfg(feature = "tracing")]
+        span_trace: Option<SpanTrace>,
+    },
+    #[error("Invalid type at {coding_path}: expected {expected}, found {found}")]
+    InvalidType {
+        coding_path: CodingPathBuf,
+        expected: String,
+        found: String,
+        #[cfg(feature = "tracing")]
+        span_trace: Option<SpanTrace>,
+    },
+    #[error("Unknown variant `{variant}` at {coding_path}, expected one of: {}", expected.join(", "))]
+    UnknownVariant {
+        coding_path: CodingPathBuf,
+        variant: String,
+        expected: &'static [&'static str],
+        #[cfg(feature = "tracing")]
+        span_trace: Option<SpanTrace>,
+    },
+    #[error("Invalid length {len} at {coding_path}, expected {expected}")]
+    InvalidLength {
+        coding_path: CodingPathBuf,
+        len: usize,
+        expected: String,
+        #[cfg(feature = "tracing")]
+        span_trace: Option<SpanTrace>,
+    },
+    #[error("Value {value} at {coding_path} is out of range for {expected}")]
+    OutOfRange {
+        coding_path: CodingPathBuf,
+        value: String,
+        expected: String,
+        #[cfg(feature = "tracing")]
+        span_trace: Option<SpanTrace>,
+    },
+    #[error("Limit exceeded at {coding_path}: {} over {max}", limit.as_str())]
+    LimitExceeded {
+        coding_path: CodingPathBuf,
+        limit: Limit,
+        max: usize,
+        #[cfg(feature = "tracing")]
+        span_trace: Option<SpanTrace>,
+    },
+    #[error("{0}: {1}")]
+    Custom(
+        CodingPathBuf,
+        String,
+        #[cfg(feature = "tracing")] Option<SpanTrace>,
+    ),
+    /// Every error recorded while decoding with
+    /// [`JsonOptions::collect_errors`], in the order they were found.
+    #[error("{} errors: {}", .0.len(), .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Multiple(Vec<Error>),
+}
+
+impl Error {
+    /// The path of the error, or of the first of several collected errors.
+    /// An empty [`Error::Multiple`] is reported at the root.
+    pub fn coding_path(&self) -> &CodingPathBuf {
+        static ROOT: CodingPathBuf = CodingPathBuf::root();
+        match self {
+            Error::Custom(coding_path, ..) => coding_path,
+            Error::KeyNotFound { coding_path, .. }
+            | Error::InvalidType { coding_path, .. }
+            | Error::UnknownVariant { coding_path, .. }
+            | Error::InvalidLength { coding_path, .. }
+            | Error::OutOfRange { coding_path, .. }
+            | Error::LimitExceeded { coding_path, .. } => coding_path,
+            Error::Multiple(errors) => errors.first().map_or(&ROOT, Error::coding_path),
+        }
+    }
+
+    /// The span trace captured where `codable` built the error, if an
+    /// [`ErrorLayer`](tracing_error::ErrorLayer) was installed, or that of the
+    /// first of several collected errors. Errors this crate builds itself have
+    /// none.
+    #[cfg(feature = "tracing")]
+    pub fn span_trace(&self) -> Option<&SpanTrace> {
+        match self {
+            Error::Custom(_, _, span_trace)
+            | Error::KeyNotFound { span_trace, .. }
+            | Error::InvalidType { span_trace, .. }
+            | Error::UnknownVariant { span_trace, .. }
+            | Error::InvalidLength { span_trace, .. }
+            | Error::OutOfRange { span_trace, .. }
+            | Error::LimitExceeded { span_trace, .. } => span_trace.as_ref(),
+            Error::Multiple(errors) => errors.first().and_then(Error::span_trace),
+        }
+    }
+
+    /// The individual errors, which is more than one only for
+    /// [`Error::Multiple`].
+    pub fn errors(&self) -> &[Error] {
+        match self {
+            Error::Multiple(errors) => errors,
+            error => std::slice::from_ref(error),
+        }
+    }
 }
 
 impl CustomError for Error {
-    fn custom(coding_path: String, message: String) -> Self {
-        Self::Custom(coding_path, message)
+    fn custom(coding_path: CodingPathBuf, message: String) -> Self {
+        Self::Custom(
+            coding_path,
+            message,
+            #[cfg(feature = "tracing")]
+            None,
+        )
+    }
+
+    fn missing_key(coding_path: CodingPathBuf, key: &str) -> Self {
+        Self::KeyNotFound {
+            coding_path,
+            key: key.to_string(),
+            #[cfg(feature = "tracing")]
+            span_trace: None,
+        }
+    }
+
+    fn invalid_type(coding_path: CodingPathBuf, expected: &str, found: &str) -> Self {
+        Self::InvalidType {
+            coding_path,
+            expected: expected.to_string(),
+            found: found.to_string(),
+            #[cfg(feature = "tracing")]
+            span_trace: None,
+        }
+    }
+
+    fn unknown_variant(
+        coding_path: CodingPathBuf,
+        variant: &str,
+        expected: &'static [&'static str],
+    ) -> Self {
+        Self::UnknownVariant {
+            coding_path,
+            variant: variant.to_string(),
+            expected,
+            #[cfg(feature = "tracing")]
+            span_trace: None,
+        }
+    }
+
+    fn invalid_length(coding_path: CodingPathBuf, len: usize, expected: &str) -> Self {
+        Self::InvalidLength {
+            coding_path,
+            len,
+            expected: expected.to_string(),
+            #[cfg(feature = "tracing")]
+            span_trace: None,
+        }
+    }
+
+    fn out_of_range(coding_path: CodingPathBuf, value: &str, expected: &str) -> Self {
+        Self::OutOfRange {
+            coding_path,
+            value: value.to_string(),
+            expected: expected.to_string(),
+            #[cfg(feature = "tracing")]
+            span_trace: None,
+        }
+    }
+
+    fn limit_exceeded(coding_path: CodingPathBuf, limit: Limit, max: usize) -> Self {
+        Self::LimitExceeded {
+            coding_path,
+            limit,
+            max,
+            #[cfg(feature = "tracing")]
+            span_trace: None,
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    fn with_span_trace(mut self, trace: SpanTrace) -> Self {
+        match &mut self {
+            Error::Custom(_, _, span_trace)
+            | Error::KeyNotFound { span_trace, .. }
+            | Error::InvalidType { span_trace, .. }
+            | Error::UnknownVariant { span_trace, .. }
+            | Error::InvalidLength { span_trace, .. }
+            | Error::OutOfRange { span_trace, .. }
+            | Error::LimitExceeded { span_trace, .. } => *span_trace = Some(trace),
+            Error::Multiple(_) => {}
+        }
+        self
     }
 }
 
+/// Decodes from a [`Value`] that lives for `'a`, at a coding path that lives
+/// for `'p`.
 #[derive(Debug, Clone)]
-pub struct JsonDecoder<'a> {
-    coding_path: CodingPath<'a>,
+pub struct JsonDecoder<'a, 'p> {
+    coding_path: CodingPath<'p>,
+    options: Arc<JsonOptions>,
+    errors: Option<ErrorCollector<Error>>,
     value: &'a Value,
 }
 
-impl<'a> JsonDecoder<'a> {
-    pub(crate) fn new(coding_path: CodingPath<'a>, value: &'a Value) -> Self {
-        Self { coding_path, value }
+impl<'a, 'p> JsonDecoder<'a, 'p> {
+    pub fn with_options(value: &'a Value, options: JsonOptions) -> Self {
+        let errors = options.collect_errors.then(ErrorCollector::new);
+        Self::new(CodingPath::root(), value, Arc::new(options), errors)
+    }
+
+    pub(crate) fn new(
+        coding_path: CodingPath<'p>,
+        value: &'a Value,
+        options: Arc<JsonOptions>,
+        errors: Option<ErrorCollector<Error>>,
+    ) -> Self {
+        Self {
+            coding_path,
+            options,
+            errors,
+            value,
+        }
+    }
+
+    /// Decodes a `T`, returning [`Error::Multiple`] if errors were collected.
+    pub fn decode<T: Decode<'a>>(&mut self) -> Result<T, Error> {
+        let result = T::decode(self);
+        match self.errors.as_ref().map(ErrorCollector::take) {
+            Some(errors) if !errors.is_empty() => Err(Error::Multiple(errors)),
+            _ => result,
+        }
     }
 }
 
-pub struct KeyedContainer<'a> {
-    coding_path: CodingPath<'a>,
+pub struct KeyedContainer<'a, 'p> {
+    coding_path: CodingPath<'p>,
+    options: Arc<JsonOptions>,
+    errors: Option<ErrorCollector<Error>>,
     value: &'a IndexMap<String, Value>,
 }
 
-pub struct ValueContainer<'a> {
-    coding_path: CodingPath<'a>,
+pub struct ValueContainer<'a, 'p> {
+    coding_path: CodingPath<'p>,
+    options: Arc<JsonOptions>,
+    errors: Option<ErrorCollector<Error>>,
     value: &'a Value,
 }
 
-pub struct SeqContainer<'a> {
-    coding_path: CodingPath<'a>,
+pub struct SeqContainer<'a, 'p> {
+    coding_path: CodingPath<'p>,
+    options: Arc<JsonOptions>,
+    errors: Option<ErrorCollector<Error>>,
     value: &'a Vec<Value>,
     cursor_index: usize,
 }
 
-impl<'a> KeyedContainer<'a> {
-    fn new(coding_path: &CodingPath<'a>, value: &'a IndexMap<String, Value>) -> Self {
+impl<'a, 'p> KeyedContainer<'a, 'p> {
+    fn new(
+        coding_path: &CodingPath<'p>,
+        options: &Arc<JsonOptions>,
+        errors: &Option<ErrorCollector<Error>>,
+        value: &'a IndexMap<String, Value>,
+    ) -> Self {
         Self {
             coding_path: coding_path.clone(),
+            options: options.clone(),
+            errors: errors.clone(),
             value,
         }
     }
 }
 
-impl<'a> ValueContainer<'a> {
-    fn new(coding_path: &CodingPath<'a>, value: &'a Value) -> Self {
+impl<'a, 'p> ValueContainer<'a, 'p> {
+    fn new(
+        coding_path: &CodingPath<'p>,
+        options: &Arc<JsonOptions>,
+        errors: &Option<ErrorCollector<Error>>,
+        value: &'a Value,
+    ) -> Self {
         Self {
             coding_path: coding_path.clone(),
+            options: options.clone(),
+            errors: errors.clone(),
             value,
         }
     }
 }
 
-impl<'a> SeqContainer<'a> {
-    fn new(coding_path: &CodingPath<'a>, value: &'a Vec<Value>) -> Self {
+impl<'a, 'p> SeqContainer<'a, 'p> {
+    fn new(
+        coding_path: &CodingPath<'p>,
+        options: &Arc<JsonOptions>,
+        errors: &Option<ErrorCollector<Error>>,
+        value: &'a Vec<Value>,
+    ) -> Self {
         Self {
-            coding_path: coding_path.join(CodingKey::Int(0)),
+            coding_path: coding_path.join_shared(CodingKey::Int(0)),
+            options: options.clone(),
+            errors: errors.clone(),
             value,
             cursor_index: 0,
         }
@@ -82,32 +319,58 @@ impl<'a> SeqContainer<'a> {
 }
 
 #[inline(always)]
-fn decode_int<T, E>(value: &IndexMap<String, Value>, key: &str) -> Result<T, E>
+fn parse_number<T>(coding_path: &CodingPath<'_>, value: &str) -> Result<T, Error>
 where
     T: FromStr,
 {
-    match value.get(&*key.as_str()) {
-        Some(Value::Number(x)) => {
-            let n = match x.parse() {
-                Ok(v) => v,
-                Err(_) => panic!(),
-            };
-            Ok(n)
-        }
-        Some(_other) => {
-            todo!()
-        }
-        None => {
-            todo!()
+    value.parse().map_err(|_| {
+        let ty = std::any::type_name::<T>();
+        // A whole number that does not parse is too big or small for `T`;
+        // anything else, such as `1.5` read as an integer, is not the kind of
+        // number `T` holds.
+        let digits = value.strip_prefix('-').unwrap_or(value);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            Error::out_of_range(coding_path.to_path_buf(), value, ty)
+        } else {
+            Error::invalid_type(coding_path.to_path_buf(), ty, &format!("number {value}"))
         }
+    })
+}
+
+/// The error for a missing or null value at `coding_path`, reported against
+/// the container it was expected in.
+fn key_not_found(coding_path: &CodingPath<'_>) -> Error {
+    let container = coding_path
+        .parent()
+        .map_or_else(CodingPathBuf::root, CodingPath::to_path_buf);
+    Error::missing_key(container, &coding_path.key().to_string())
+}
+
+#[inline(always)]
+fn decode_int<T>(
+    coding_path: &CodingPath<'_>,
+    value: &IndexMap<String, Value>,
+    key: &str,
+) -> Result<T, Error>
+where
+    T: FromStr,
+{
+    match value.get(key) {
+        Some(Value::Number(x)) => parse_number(&coding_path.join(key.to_coding_key()), x),
+        Some(Value::Null) | None => Err(Error::missing_key(coding_path.to_path_buf(), key)),
+        Some(other) => Err(Error::invalid_type(
+            coding_path.join(key.to_coding_key()).to_path_buf(),
+            "number",
+            other.kind(),
+        )),
     }
 }
 
-impl<'c> dec::KeyedContainer for KeyedContainer<'c> {
+impl<'c, 'p> dec::KeyedContainer<'c> for KeyedContainer<'c, 'p> {
     type Error = Error;
     type Value = Value;
 
-    type Decoder = JsonDecoder<'c>;
+    type Decoder = JsonDecoder<'c, 'p>;
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn coding_path(&self) -> &CodingPath<'_> {
         &self.coding_path
@@ -128,142 +391,184 @@ impl<'c> dec::KeyedContainer for KeyedContainer<'c> {
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u8(&mut self, key: &impl ToCodingKey) -> Result<u8, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u16(&mut self, key: &impl ToCodingKey) -> Result<u16, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u32(&mut self, key: &impl ToCodingKey) -> Result<u32, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u64(&mut self, key: &impl ToCodingKey) -> Result<u64, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u128(&mut self, key: &impl ToCodingKey) -> Result<u128, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_usize(&mut self, key: &impl ToCodingKey) -> Result<usize, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i8(&mut self, key: &impl ToCodingKey) -> Result<i8, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i16(&mut self, key: &impl ToCodingKey) -> Result<i16, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i32(&mut self, key: &impl ToCodingKey) -> Result<i32, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i64(&mut self, key: &impl ToCodingKey) -> Result<i64, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i128(&mut self, key: &impl ToCodingKey) -> Result<i128, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_isize(&mut self, key: &impl ToCodingKey) -> Result<isize, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_string(&mut self, key: &impl ToCodingKey) -> Result<String, Self::Error> {
-        match self.value.get(&*key.as_str()) {
-            Some(Value::String(x)) => Ok(x.to_string()),
-            Some(Value::Null) => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            Some(_unknown) => Err(Error::InvalidType(self.coding_path.to_string())),
-            None => Err(Error::KeyNotFound(self.coding_path.to_string())),
+        self.decode_str(key).map(Cow::into_owned)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn decode_str(&mut self, key: &impl ToCodingKey) -> Result<Cow<'c, str>, Self::Error> {
+        let name = key.as_str();
+        match self.value.get(&*name) {
+            Some(Value::String(x)) => {
+                self.options.limits.check(Limit::StringLen, x.len(), || {
+                    self.coding_path.join(key.to_coding_key()).to_path_buf()
+                })?;
+                Ok(Cow::Borrowed(x))
+            }
+            Some(Value::Null) | None => {
+                Err(Error::missing_key(self.coding_path.to_path_buf(), &name))
+            }
+            Some(other) => Err(Error::invalid_type(
+                self.coding_path.join(key.to_coding_key()).to_path_buf(),
+                "string",
+                other.kind(),
+            )),
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn decode_bytes(&mut self, key: &impl ToCodingKey) -> Result<Cow<'c, [u8]>, Self::Error> {
+        let name = key.as_str();
+        match self.value.get(&*name) {
+            Some(Value::Null) | None => {
+                Err(Error::missing_key(self.coding_path.to_path_buf(), &name))
+            }
+            Some(value) => bytes::from_value(
+                &self.options,
+                &self.errors,
+                &self.coding_path.join(key.to_coding_key().into_owned()),
+                value,
+            )
+            .map(Cow::Owned),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_f32(&mut self, key: &impl ToCodingKey) -> Result<f32, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_f64(&mut self, key: &impl ToCodingKey) -> Result<f64, Self::Error> {
-        decode_int(self.value, &key.as_str())
+        decode_int(&self.coding_path, self.value, &key.as_str())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_bool(&mut self, key: &impl ToCodingKey) -> Result<bool, Self::Error> {
-        match self.value.get(&*key.as_str()) {
+        let name = key.as_str();
+        match self.value.get(&*name) {
             Some(Value::Bool(x)) => Ok(*x),
-            Some(_other) => {
-                todo!()
-            }
-            None => {
-                todo!()
+            Some(Value::Null) | None => {
+                Err(Error::missing_key(self.coding_path.to_path_buf(), &name))
             }
+            Some(other) => Err(Error::invalid_type(
+                self.coding_path.join(key.to_coding_key()).to_path_buf(),
+                "bool",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn decode_option<T: Decode>(
+    fn decode_option<T: Decode<'c>>(
         &mut self,
         key: &impl ToCodingKey,
     ) -> Result<Option<T>, Self::Error> {
-        match self.decode(key) {
-            Ok(x) => Ok(Some(x)),
-            Err(Error::KeyNotFound(_)) => Ok(None),
-            Err(x) => Err(x),
+        // Only a missing or null value is `None`, so that a key missing from
+        // inside a value that is present stays an error.
+        match self.value.get(&*key.as_str()) {
+            None | Some(Value::Null) => Ok(None),
+            Some(_) => self.decode(key).map(Some),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn decode<T: Decode>(&mut self, key: &impl ToCodingKey) -> Result<T, Self::Error> {
+    fn decode<T: Decode<'c>>(&mut self, key: &impl ToCodingKey) -> Result<T, Self::Error> {
         let key = key.to_coding_key();
         let path = self.coding_path.join(key.clone());
         let obj = self
             .value
             .get(&*key.as_str())
-            .ok_or_else(|| Error::KeyNotFound(path.to_string()))?;
-        T::decode(&mut JsonDecoder::new(path, obj))
+            .ok_or_else(|| Error::missing_key(self.coding_path.to_path_buf(), &key.as_str()))?;
+        T::decode(&mut JsonDecoder::new(
+            path,
+            obj,
+            self.options.clone(),
+            self.errors.clone(),
+        ))
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn nested_container<'a>(
-        &'a mut self,
+    fn nested_container(
+        &mut self,
         _key: &impl ToCodingKey,
-    ) -> Result<<Self::Decoder as dec::Decoder>::KeyedContainer, Self::Error> {
+    ) -> Result<<Self::Decoder as dec::Decoder<'c>>::KeyedContainer, Self::Error> {
         todo!()
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn nested_seq_container<'a>(
-        &'a mut self,
+    fn nested_seq_container(
+        &mut self,
         _key: &impl ToCodingKey,
-    ) -> Result<<Self::Decoder as dec::Decoder>::SeqContainer, Self::Error> {
+    ) -> Result<<Self::Decoder as dec::Decoder<'c>>::SeqContainer, Self::Error> {
         todo!()
     }
 }
 
-impl<'c> dec::ValueContainer for ValueContainer<'c> {
+impl<'c, 'p> dec::ValueContainer<'c> for ValueContainer<'c, 'p> {
     type Error = Error;
     type Value = Value;
 
-    type Decoder = JsonDecoder<'c>;
+    type Decoder = JsonDecoder<'c, 'p>;
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn coding_path(&self) -> &CodingPath<'_> {
         &self.coding_path
@@ -272,135 +577,227 @@ impl<'c> dec::ValueContainer for ValueContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u8(&mut self) -> Result<u8, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u16(&mut self) -> Result<u16, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u32(&mut self) -> Result<u32, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u64(&mut self) -> Result<u64, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u128(&mut self) -> Result<u128, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_usize(&mut self) -> Result<usize, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i8(&mut self) -> Result<i8, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i16(&mut self) -> Result<i16, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i32(&mut self) -> Result<i32, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i64(&mut self) -> Result<i64, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i128(&mut self) -> Result<i128, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_isize(&mut self) -> Result<isize, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_string(&mut self) -> Result<String, Self::Error> {
+        self.decode_str().map(Cow::into_owned)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn decode_str(&mut self) -> Result<Cow<'c, str>, Self::Error> {
         match self.value {
-            Value::String(x) => Ok(x.to_string()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::String(x) => {
+                self.options.limits.check(Limit::StringLen, x.len(), || {
+                    self.coding_path.to_path_buf()
+                })?;
+                Ok(Cow::Borrowed(x))
+            }
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "string",
+                other.kind(),
+            )),
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn decode_bytes(&mut self) -> Result<Cow<'c, [u8]>, Self::Error> {
+        match self.value {
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            value => bytes::from_value(&self.options, &self.errors, &self.coding_path, value)
+                .map(Cow::Owned),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_f32(&mut self) -> Result<f32, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_f64(&mut self) -> Result<f64, Self::Error> {
         match self.value {
-            Value::Number(x) => Ok(x.parse().unwrap()),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn decode_number_str(&mut self) -> Result<Cow<'c, str>, Self::Error> {
+        match self.value {
+            Value::Number(x) => Ok(Cow::Borrowed(x)),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "number",
+                other.kind(),
+            )),
         }
     }
 
@@ -408,50 +805,67 @@ impl<'c> dec::ValueContainer for ValueContainer<'c> {
     fn decode_bool(&mut self) -> Result<bool, Self::Error> {
         match self.value {
             Value::Bool(x) => Ok(*x),
-            Value::Null => Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Null => Err(key_not_found(&self.coding_path)),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "bool",
+                other.kind(),
+            )),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn decode_option<T: Decode>(&mut self) -> Result<Option<T>, Self::Error> {
-        match self.decode() {
-            Ok(v) => Ok(Some(v)),
-            Err(Error::KeyNotFound(_)) => Ok(None),
-            Err(e) => Err(e),
+    fn decode_option<T: Decode<'c>>(&mut self) -> Result<Option<T>, Self::Error> {
+        match self.value {
+            Value::Null => Ok(None),
+            _ => self.decode().map(Some),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn decode<T: Decode>(&mut self) -> Result<T, Self::Error> {
+    fn decode<T: Decode<'c>>(&mut self) -> Result<T, Self::Error> {
         T::decode(&mut JsonDecoder::new(
-            self.coding_path().clone(),
+            self.coding_path.clone(),
             self.value,
+            self.options.clone(),
+            self.errors.clone(),
         ))
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_null(&mut self) -> Result<(), Self::Error> {
-        Ok(())
+        match self.value {
+            Value::Null => Ok(()),
+            other => Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "null",
+                other.kind(),
+            )),
+        }
     }
 }
 
-impl SeqContainer<'_> {
+impl SeqContainer<'_, '_> {
     #[inline]
     fn increment_cursor(&mut self) {
         self.cursor_index += 1;
-        // self.coding_path.join(CodingKey::Int(self.cursor_index));
+        self.coding_path.replace(CodingKey::Int(self.cursor_index));
     }
 }
 
-impl<'c> dec::SeqContainer for SeqContainer<'c> {
+impl<'c, 'p> dec::SeqContainer<'c> for SeqContainer<'c, 'p> {
     type Error = Error;
     type Value = Value;
 
-    type Decoder = JsonDecoder<'c>;
+    type Decoder = JsonDecoder<'c, 'p>;
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn len(&self) -> usize {
-        self.value.len()
+    fn len(&self) -> Option<usize> {
+        Some(self.value.len())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn is_at_end(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.cursor_index >= self.value.len())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
@@ -467,9 +881,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u8(&mut self) -> Result<u8, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -478,9 +898,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u16(&mut self) -> Result<u16, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -489,9 +915,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u32(&mut self) -> Result<u32, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -500,9 +932,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u64(&mut self) -> Result<u64, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -511,9 +949,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_u128(&mut self) -> Result<u128, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -522,9 +966,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_usize(&mut self) -> Result<usize, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -533,9 +983,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i8(&mut self) -> Result<i8, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -544,9 +1000,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i16(&mut self) -> Result<i16, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -555,9 +1017,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i32(&mut self) -> Result<i32, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -566,9 +1034,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i64(&mut self) -> Result<i64, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -577,9 +1051,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_i128(&mut self) -> Result<i128, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -588,9 +1068,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_isize(&mut self) -> Result<isize, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -598,21 +1084,53 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_string(&mut self) -> Result<String, Self::Error> {
+        self.decode_str().map(Cow::into_owned)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn decode_str(&mut self) -> Result<Cow<'c, str>, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::String(x) => x.to_string(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::String(x) => {
+                self.options.limits.check(Limit::StringLen, x.len(), || {
+                    self.coding_path.to_path_buf()
+                })?;
+                Cow::Borrowed(x.as_str())
+            }
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "string",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn decode_bytes(&mut self) -> Result<Cow<'c, [u8]>, Self::Error> {
+        let result = match &self.value[self.cursor_index] {
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            value => bytes::from_value(&self.options, &self.errors, &self.coding_path, value)?,
+        };
+        self.increment_cursor();
+        Ok(Cow::Owned(result))
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_f32(&mut self) -> Result<f32, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -621,9 +1139,15 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn decode_f64(&mut self) -> Result<f64, Self::Error> {
         let result = match &self.value[self.cursor_index] {
-            Value::Number(x) => x.parse().unwrap(),
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Number(x) => parse_number(&self.coding_path, x)?,
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "number",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
@@ -633,73 +1157,498 @@ impl<'c> dec::SeqContainer for SeqContainer<'c> {
     fn decode_bool(&mut self) -> Result<bool, Self::Error> {
         let result = match &self.value[self.cursor_index] {
             Value::Bool(x) => *x,
-            Value::Null => return Err(Error::KeyNotFound(self.coding_path.to_string())),
-            _unknown => return Err(Error::InvalidType(self.coding_path.to_string())),
+            Value::Null => return Err(key_not_found(&self.coding_path)),
+            other => {
+                return Err(Error::invalid_type(
+                    self.coding_path.to_path_buf(),
+                    "bool",
+                    other.kind(),
+                ))
+            }
         };
         self.increment_cursor();
         Ok(result)
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn decode_option<T: Decode>(&mut self) -> Result<Option<T>, Self::Error> {
-        match self.decode() {
-            Ok(v) => Ok(Some(v)),
-            Err(Error::KeyNotFound(_)) => Ok(None),
-            Err(e) => Err(e),
+    fn decode_option<T: Decode<'c>>(&mut self) -> Result<Option<T>, Self::Error> {
+        match self.value.get(self.cursor_index) {
+            Some(Value::Null) => {
+                self.increment_cursor();
+                Ok(None)
+            }
+            _ => self.decode().map(Some),
         }
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn decode<T: Decode>(&mut self) -> Result<T, Self::Error> {
+    fn decode<T: Decode<'c>>(&mut self) -> Result<T, Self::Error> {
         let item = &self.value[self.cursor_index];
-        let result = T::decode(&mut JsonDecoder::new(self.coding_path().clone(), item))?;
+        let result = T::decode(&mut JsonDecoder::new(
+            self.coding_path.clone(),
+            item,
+            self.options.clone(),
+            self.errors.clone(),
+        ));
+        // Move past the element even if it failed, so decoding can carry on
+        // when errors are being collected.
         self.increment_cursor();
-        Ok(result)
+        result
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn nested_container<'a>(
-        &'a mut self,
-    ) -> Result<<Self::Decoder as dec::Decoder>::KeyedContainer, Self::Error> {
+    fn nested_container(
+        &mut self,
+    ) -> Result<<Self::Decoder as dec::Decoder<'c>>::KeyedContainer, Self::Error> {
         todo!()
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
-    fn nested_seq_container<'a>(
-        &'a mut self,
-    ) -> Result<<Self::Decoder as dec::Decoder>::SeqContainer, Self::Error> {
+    fn nested_seq_container(
+        &mut self,
+    ) -> Result<<Self::Decoder as dec::Decoder<'c>>::SeqContainer, Self::Error> {
         todo!()
     }
 }
 
-impl<'r> Decoder for JsonDecoder<'r> {
+impl<'r, 'p> Decoder<'r> for JsonDecoder<'r, 'p> {
     type Value = Value;
     type Error = Error;
 
-    type KeyedContainer = KeyedContainer<'r> where Self: 'r;
-    type ValueContainer = ValueContainer<'r> where Self: 'r;
-    type SeqContainer = SeqContainer<'r> where Self: 'r;
+    type KeyedContainer = KeyedContainer<'r, 'p>;
+    type ValueContainer = ValueContainer<'r, 'p>;
+    type SeqContainer = SeqContainer<'r, 'p>;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    fn is_human_readable(&self) -> bool {
+        !self.options.compact
+    }
+
+    fn user_info(&self) -> &UserInfo {
+        &self.options.user_info
+    }
+
+    fn error_collector(&self) -> Option<&ErrorCollector<Self::Error>> {
+        self.errors.as_ref()
+    }
+
+    fn limits(&self) -> &Limits {
+        &self.options.limits
+    }
+
+    #[cfg(feature = "chrono")]
+    fn date_decoding_strategy(&self) -> &codable::date::DateDecodingStrategy {
+        &self.options.date_decoding
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
+    fn peek_kind(&mut self) -> Result<ValueKind, Self::Error> {
+        Ok(match self.value {
+            Value::Null => ValueKind::Null,
+            Value::Bool(_) => ValueKind::Bool,
+            Value::Number(x) if x.parse::<i128>().is_ok() || x.parse::<u128>().is_ok() => {
+                ValueKind::Int
+            }
+            Value::Number(_) => ValueKind::Float,
+            Value::String(_) => ValueKind::String,
+            Value::Array(_) => ValueKind::Seq,
+            Value::Object(_) => ValueKind::Map,
+        })
+    }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn as_container(&mut self) -> Result<Self::KeyedContainer, Self::Error> {
         let map = self.value.as_map(&self.coding_path)?;
-        Ok(KeyedContainer::new(&self.coding_path, map))
+        let limits = &self.options.limits;
+        limits.check_depth(&self.coding_path)?;
+        limits.check(Limit::MapLen, map.len(), || self.coding_path.to_path_buf())?;
+        Ok(KeyedContainer::new(
+            &self.coding_path,
+            &self.options,
+            &self.errors,
+            map,
+        ))
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn as_value_container(&mut self) -> Result<Self::ValueContainer, Self::Error> {
-        if !self.value.is_scalar() {
-            return Err(Error::InvalidType(self.coding_path.to_string()));
+        // Byte strings may be represented as arrays, which are still decoded
+        // through a value container's `decode_bytes`.
+        let is_bytes =
+            self.options.bytes == BytesFormat::Array && matches!(self.value, Value::Array(_));
+        if !self.value.is_scalar() && !is_bytes {
+            return Err(Error::invalid_type(
+                self.coding_path.to_path_buf(),
+                "scalar",
+                self.value.kind(),
+            ));
         }
 
-        Ok(ValueContainer::new(&self.coding_path, self.value))
+        Ok(ValueContainer::new(
+            &self.coding_path,
+            &self.options,
+            &self.errors,
+            self.value,
+        ))
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(coding_path = ?self.coding_path)))]
     fn as_seq_container(&mut self) -> Result<Self::SeqContainer, Self::Error> {
+        let array = self.value.as_array(&self.coding_path)?;
+        let limits = &self.options.limits;
+        limits.check_depth(&self.coding_path)?;
+        limits.check(Limit::SeqLen, array.len(), || self.coding_path.to_path_buf())?;
         Ok(SeqContainer::new(
             &self.coding_path,
-            self.value.as_array(&self.coding_path)?,
+            &self.options,
+            &self.errors,
+            array,
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::{from_value, from_value_with, test_util::collecting, to_value};
+
+    use super::*;
+
+    #[test]
+    fn typed_errors() {
+        let value = Value::Number("300".into());
+        assert_matches!(
+            from_value::<u8>(&value),
+            Err(Error::OutOfRange {
+                value, expected, ..
+            }) => {
+                assert_eq!(value, "300");
+                assert_eq!(expected, "u8");
+            }
+        );
+
+        let value = Value::Number("1.5".into());
+        assert_matches!(
+            from_value::<u8>(&value),
+            Err(Error::InvalidType {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "u8");
+                assert_eq!(found, "number 1.5");
+            }
+        );
+
+        let value = Value::String("nope".into());
+        assert_matches!(
+            from_value::<u32>(&value),
+            Err(Error::InvalidType {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "number");
+                assert_eq!(found, "string");
+            }
+        );
+
+        let value = Value::Array(vec![]);
+        assert!(matches!(
+            from_value::<BTreeMap<String, u8>>(&value),
+            Err(Error::InvalidType { .. })
+        ));
+    }
+
+    #[test]
+    fn collect_errors() {
+        use codable::{Decode, Encode};
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        struct Item {
+            name: String,
+            count: u8,
+        }
+
+        let item = |name: Value, count: &str| {
+            let mut item = IndexMap::new();
+            item.insert("name".to_string(), name);
+            item.insert("count".to_string(), Value::Number(count.into()));
+            Value::Object(item)
+        };
+        let mut map = IndexMap::new();
+        map.insert(
+            "a".to_string(),
+            Value::Array(vec![
+                item(Value::String("x".into()), "1"),
+                item(Value::Bool(true), "256"),
+                item(Value::String("y".into()), "2"),
+            ]),
+        );
+        map.insert("b".to_string(), Value::Array(vec![item(Value::Null, "3")]));
+        let value = Value::Object(map);
+
+        // Without collecting, decoding stops at the first problem.
+        let error = from_value::<BTreeMap<String, Vec<Item>>>(&value).unwrap_err();
+        assert_eq!(error.errors().len(), 1);
+        assert_eq!(error.coding_path().to_string(), "a[1].name");
+
+        let error =
+            from_value_with::<BTreeMap<String, Vec<Item>>>(&value, collecting()).unwrap_err();
+        let paths = error
+            .errors()
+            .iter()
+            .map(|e| e.coding_path().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["a[1].name", "a[1].count", "b[0]"]);
+        assert_matches!(
+            error.errors(),
+            [Error::InvalidType { .. }, Error::OutOfRange { .. }, Error::KeyNotFound { key, .. }]
+                if key == "name"
+        );
+        assert!(error.to_string().starts_with("3 errors: "));
+
+        let value = Value::Array(vec![item(Value::String("x".into()), "1")]);
+        let items = vec![Item {
+            name: "x".into(),
+            count: 1,
+        }];
+        assert_eq!(
+            from_value_with::<Vec<Item>>(&value, collecting()).unwrap(),
+            items
+        );
+        assert_eq!(to_value(&items).unwrap(), value);
+    }
+
+    #[test]
+    fn missing_keys_inside_options() {
+        use codable::Decode;
+
+        #[derive(Debug, PartialEq, Decode)]
+        struct Inner {
+            x: u32,
+        }
+
+        #[derive(Debug, PartialEq, Decode)]
+        struct Outer {
+            inner: Option<Inner>,
+        }
+
+        for options in [JsonOptions::default(), collecting()] {
+            let value = Value::Object(IndexMap::new());
+            let outer = from_value_with::<Outer>(&value, options.clone()).unwrap();
+            assert_eq!(outer, Outer { inner: None });
+
+            let value = Value::Object(IndexMap::from([("inner".to_string(), Value::Null)]));
+            let outer = from_value_with::<Outer>(&value, options.clone()).unwrap();
+            assert_eq!(outer, Outer { inner: None });
+
+            // The key missing from a value that is present is an error.
+            let value = Value::Object(IndexMap::from([(
+                "inner".to_string(),
+                Value::Object(IndexMap::new()),
+            )]));
+            let error = from_value_with::<Outer>(&value, options).unwrap_err();
+            assert_matches!(
+                error.errors(),
+                [Error::KeyNotFound {
+                    coding_path, key, ..
+                }] => {
+                    assert_eq!(coding_path.to_string(), "inner");
+                    assert_eq!(key, "x");
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn seq_element_paths() {
+        let number = |x: &str| Value::Number(x.into());
+        let value = Value::Array(vec![number("1"), number("2"), Value::Bool(true)]);
+        let err = from_value::<Vec<u8>>(&value).unwrap_err();
+        assert_eq!(err.coding_path().to_string(), "[2]");
+
+        let mut good = IndexMap::new();
+        good.insert("a".to_string(), Value::Array(vec![number("1")]));
+        let mut bad = IndexMap::new();
+        bad.insert(
+            "a".to_string(),
+            Value::Array(vec![number("1"), number("2"), number("3"), number("-4")]),
+        );
+        let value = Value::Array(vec![Value::Object(good), Value::Object(bad)]);
+        let err = from_value::<Vec<BTreeMap<String, Vec<u8>>>>(&value).unwrap_err();
+        assert!(matches!(err, Error::OutOfRange { .. }));
+        assert_eq!(err.coding_path().to_string(), "[1].a[3]");
+        assert_eq!(err.coding_path().json_pointer().to_string(), "/1/a/3");
+    }
+
+    #[test]
+    fn error_paths() {
+        let mut inner = IndexMap::new();
+        inner.insert("a.b".to_string(), Value::String("x".into()));
+        let mut outer = IndexMap::new();
+        outer.insert("a".to_string(), Value::Object(inner));
+        let value = Value::Object(outer);
+
+        let err = from_value::<BTreeMap<String, BTreeMap<String, u8>>>(&value).unwrap_err();
+        assert_eq!(
+            err.coding_path().keys(),
+            &[
+                CodingKey::String("a".into()),
+                CodingKey::String("a.b".into())
+            ]
+        );
+        assert_eq!(err.coding_path().to_string(), r#"a."a.b""#);
+        assert_eq!(err.coding_path().json_pointer().to_string(), "/a/a.b");
+
+        let mut decoder = JsonDecoder::with_options(&value, Default::default());
+        let mut c = decoder.as_container().unwrap();
+        let err = dec::KeyedContainer::decode_u8(&mut c, &"missing").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::KeyNotFound { ref coding_path, ref key, .. }
+                if coding_path.keys().is_empty() && key == "missing"
+        ));
+
+        let err = Error::Multiple(vec![]);
+        assert!(err.coding_path().is_empty());
+        assert!(err.errors().is_empty());
+    }
+
+    #[test]
+    fn default_constructors() {
+        struct Message(String);
+
+        impl CustomError for Message {
+            fn custom(coding_path: CodingPathBuf, message: String) -> Self {
+                Message(format!("{coding_path}: {message}"))
+            }
+        }
+
+        let path = |x: &str| CodingPathBuf::root().join(x.to_coding_key());
+        assert_eq!(Message::missing_key(path("a"), "c").0, "a: missing key `c`");
+        assert_eq!(
+            Message::unknown_variant(path("kind"), "d", &["a", "b"]).0,
+            "kind: unknown variant `d`, expected one of: a, b"
+        );
+        assert_eq!(
+            Message::invalid_length(path("xs"), 2, "3 elements").0,
+            "xs: invalid length 2, expected 3 elements"
+        );
+    }
+
+    #[test]
+    fn borrowed_strings() {
+        use codable::dec::{DecodeResult, KeyedContainer as _};
+
+        struct Message<'a> {
+            id: &'a str,
+            tags: Vec<Cow<'a, str>>,
+        }
+
+        impl<'de> Decode<'de> for Message<'de> {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                let mut c = decoder.as_container()?;
+                Ok(Message {
+                    id: c.decode(&"id")?,
+                    tags: c.decode(&"tags")?,
+                })
+            }
+        }
+
+        let value = Value::Object(IndexMap::from([
+            ("id".to_string(), Value::String("m-1".into())),
+            (
+                "tags".to_string(),
+                Value::Array(vec![Value::String("a".into()), Value::String("b".into())]),
+            ),
+        ]));
+
+        let message = from_value::<Message<'_>>(&value).unwrap();
+        let Value::Object(map) = &value else {
+            unreachable!()
+        };
+        let Value::String(id) = &map["id"] else {
+            unreachable!()
+        };
+        assert_eq!(message.id, "m-1");
+        assert!(std::ptr::eq(message.id, id.as_str()));
+        assert!(message.tags.iter().all(|x| matches!(x, Cow::Borrowed(_))));
+        assert_eq!(message.tags, ["a", "b"]);
+
+        let value = Value::Number("1".into());
+        assert!(matches!(
+            from_value::<&str>(&value),
+            Err(Error::InvalidType { .. })
+        ));
+    }
+
+    #[test]
+    fn limits() {
+        use crate::test_util::with_limits;
+
+        let exceeded = |value: &Value, limits: Limits| {
+            assert_matches!(
+                from_value_with::<Value>(value, with_limits(limits)),
+                Err(Error::LimitExceeded {
+                    coding_path,
+                    limit,
+                    max,
+                    ..
+                }) => (coding_path.to_string(), limit, max)
+            )
+        };
+
+        let nested = (0..3).fold(Value::Number("1".into()), |x, _| Value::Array(vec![x]));
+        let depth = |max| Limits {
+            max_depth: Some(max),
+            ..Default::default()
+        };
+        assert!(from_value_with::<Value>(&nested, with_limits(depth(3))).is_ok());
+        assert_eq!(
+            exceeded(&nested, depth(2)),
+            ("[0][0]".to_string(), Limit::Depth, 2)
+        );
+
+        let array = Value::Array(vec![Value::Null; 3]);
+        let limits = Limits {
+            max_seq_len: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            exceeded(&array, limits),
+            ("<root>".to_string(), Limit::SeqLen, 2)
+        );
+
+        let map = Value::Object(IndexMap::from([
+            ("a".to_string(), Value::String("short".into())),
+            ("b".to_string(), Value::String("much longer".into())),
+        ]));
+        let limits = Limits {
+            max_map_len: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            exceeded(&map, limits),
+            ("<root>".to_string(), Limit::MapLen, 1)
+        );
+
+        let limits = Limits {
+            max_string_len: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(
+            exceeded(&map, limits),
+            ("b".to_string(), Limit::StringLen, 5)
+        );
+        assert_eq!(
+            from_value_with::<Value>(&map, with_limits(limits))
+                .unwrap_err()
+                .to_string(),
+            "Limit exceeded at b: string length over 5"
+        );
+    }
+}
@@ -1,18 +1,38 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 
 use indexmap::IndexMap;
 
 use codable::{
-    enc::{self, Encode, Encoder},
-    CodingPath, ToCodingKey,
+    enc::{self, CustomError, Encode, Encoder},
+    CodingKey, CodingPath, CodingPathBuf, ToCodingKey, UserInfo,
 };
 
-use crate::Value;
+#[cfg(feature = "tracing")]
+use tracing_error::SpanTrace;
+
+use crate::{bytes, JsonOptions, Value};
 
 #[derive(Debug)]
 pub enum Error {
     KeyNotFound,
     InvalidType,
+    Custom(
+        CodingPathBuf,
+        String,
+        #[cfg(feature = "tracing")] Option<SpanTrace>,
+    ),
+}
+
+impl Error {
+    /// The span trace captured where `codable` built the error, if an
+    /// [`ErrorLayer`](tracing_error::ErrorLayer) was installed.
+    #[cfg(feature = "tracing")]
+    pub fn span_trace(&self) -> Option<&SpanTrace> {
+        match self {
+            Error::Custom(_, _, span_trace) => span_trace.as_ref(),
+            Error::KeyNotFound | Error::InvalidType => None,
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -20,24 +40,56 @@ impl std::fmt::Display for Error {
         match self {
             Error::KeyNotFound => write!(f, "key not found"),
             Error::InvalidType => write!(f, "invalid type"),
+            Error::Custom(coding_path, message, ..) => write!(f, "{coding_path}: {message}"),
+        }
+    }
+}
+
+impl CustomError for Error {
+    fn custom(coding_path: CodingPathBuf, message: String) -> Self {
+        Self::Custom(
+            coding_path,
+            message,
+            #[cfg(feature = "tracing")]
+            None,
+        )
+    }
+
+    #[cfg(feature = "tracing")]
+    fn with_span_trace(mut self, trace: SpanTrace) -> Self {
+        if let Error::Custom(_, _, span_trace) = &mut self {
+            *span_trace = Some(trace);
         }
+        self
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct JsonEncoder<'a> {
     coding_path: CodingPath<'a>,
+    options: Arc<JsonOptions>,
+}
+
+impl<'a> Default for JsonEncoder<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> JsonEncoder<'a> {
     pub fn new() -> Self {
-        Self {
-            coding_path: CodingPath::root(),
-        }
+        Self::with_options(JsonOptions::default())
     }
 
-    pub(crate) fn with_path(coding_path: CodingPath<'a>) -> Self {
-        Self { coding_path }
+    pub fn with_options(options: JsonOptions) -> Self {
+        Self::with_path(CodingPath::root(), Arc::new(options))
+    }
+
+    pub(crate) fn with_path(coding_path: CodingPath<'a>, options: Arc<JsonOptions>) -> Self {
+        Self {
+            coding_path,
+            options,
+        }
     }
 }
 
@@ -50,27 +102,42 @@ impl<'r> enc::Encoder<'r> for JsonEncoder<'r> {
     type SeqContainer = SeqContainer<'r> where Self: 'r;
 
     fn as_value_container(&mut self) -> Self::ValueContainer {
-        ValueContainer::new(self.coding_path.clone())
+        ValueContainer::new(self.coding_path.clone(), self.options.clone())
     }
 
     fn as_seq_container(&mut self) -> Self::SeqContainer {
-        SeqContainer::new(self.coding_path.clone())
+        SeqContainer::new(self.coding_path.clone(), self.options.clone())
     }
 
     fn as_container(&mut self) -> Self::KeyedContainer {
-        KeyedContainer::new(self.coding_path.clone())
+        KeyedContainer::new(self.coding_path.clone(), self.options.clone())
+    }
+
+    fn is_human_readable(&self) -> bool {
+        !self.options.compact
+    }
+
+    fn user_info(&self) -> &UserInfo {
+        &self.options.user_info
+    }
+
+    #[cfg(feature = "chrono")]
+    fn date_encoding_strategy(&self) -> &codable::date::DateEncodingStrategy {
+        &self.options.date_encoding
     }
 }
 
 pub struct KeyedContainer<'a> {
     coding_path: CodingPath<'a>,
+    options: Arc<JsonOptions>,
     value: IndexMap<String, Value>,
 }
 
 impl<'a> KeyedContainer<'a> {
-    fn new(coding_path: CodingPath<'a>) -> Self {
+    fn new(coding_path: CodingPath<'a>, options: Arc<JsonOptions>) -> Self {
         Self {
             coding_path,
+            options,
             value: Default::default(),
         }
     }
@@ -88,7 +155,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_u8(&mut self, value: u8, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -96,7 +163,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_u16(&mut self, value: u16, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -104,7 +171,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_u32(&mut self, value: u32, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -112,7 +179,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_u64(&mut self, value: u64, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -120,7 +187,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_u128(&mut self, value: u128, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -128,7 +195,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_usize(&mut self, value: usize, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -136,7 +203,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_i8(&mut self, value: i8, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -144,7 +211,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_i16(&mut self, value: i16, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -152,7 +219,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_i32(&mut self, value: i32, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -160,7 +227,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_i64(&mut self, value: i64, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -168,7 +235,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_i128(&mut self, value: i128, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -176,7 +243,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
     fn encode_isize(&mut self, value: isize, key: &impl ToCodingKey) -> Result<(), Self::Error> {
         self.value.insert(
             key.as_str().to_string(),
-            Value::Number(value.to_string().into()),
+            Value::Number(value.to_string()),
         );
         Ok(())
     }
@@ -213,6 +280,14 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
         Ok(())
     }
 
+    fn encode_bytes(&mut self, value: &[u8], key: &impl ToCodingKey) -> Result<(), Self::Error> {
+        self.value.insert(
+            key.as_str().to_string(),
+            bytes::to_value(self.options.bytes, value),
+        );
+        Ok(())
+    }
+
     fn encode_option<T: Encode>(
         &mut self,
         value: Option<&T>,
@@ -227,14 +302,14 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
         }
     }
 
-    fn encode<'a, T: Encode>(
-        &'a mut self,
+    fn encode<T: Encode>(
+        &mut self,
         value: &T,
         key: &impl ToCodingKey,
     ) -> Result<(), Self::Error> {
         let coding_path = self.coding_path.join(key.to_coding_key());
         let key = key.as_str().to_string();
-        let mut encoder = JsonEncoder::with_path(coding_path);
+        let mut encoder = JsonEncoder::with_path(coding_path, self.options.clone());
         let value = value.encode(&mut encoder)?;
         self.value.insert(key, value);
         Ok(())
@@ -425,7 +500,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
         key: &'a impl ToCodingKey,
     ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::KeyedContainer, Self::Error> {
         let p = self.coding_path().join(key.to_coding_key());
-        let mut encoder = JsonEncoder::with_path(p);
+        let mut encoder = JsonEncoder::with_path(p, self.options.clone());
         Ok(encoder.as_container())
     }
 
@@ -434,7 +509,7 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
         key: &'a impl ToCodingKey,
     ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::SeqContainer, Self::Error> {
         let p = self.coding_path().join(key.to_coding_key());
-        let mut encoder = JsonEncoder::with_path(p);
+        let mut encoder = JsonEncoder::with_path(p, self.options.clone());
         Ok(encoder.as_seq_container())
     }
 
@@ -446,16 +521,22 @@ impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
 #[derive(Debug)]
 pub struct ValueContainer<'en> {
     coding_path: CodingPath<'en>,
+    options: Arc<JsonOptions>,
     value: Option<Value>,
 }
 
 impl<'en> ValueContainer<'en> {
-    pub fn new(coding_path: CodingPath<'en>) -> Self {
+    pub fn new(coding_path: CodingPath<'en>, options: Arc<JsonOptions>) -> Self {
         Self {
             coding_path,
+            options,
             value: None,
         }
     }
+
+    fn encoder(&self) -> JsonEncoder<'en> {
+        JsonEncoder::with_path(self.coding_path.clone(), self.options.clone())
+    }
 }
 
 impl<'c> enc::ValueContainer for ValueContainer<'c> {
@@ -547,6 +628,11 @@ impl<'c> enc::ValueContainer for ValueContainer<'c> {
         Ok(())
     }
 
+    fn encode_bytes(&mut self, value: &[u8]) -> Result<(), Self::Error> {
+        self.value = Some(bytes::to_value(self.options.bytes, value));
+        Ok(())
+    }
+
     fn encode_null(&mut self) -> Result<(), Self::Error> {
         self.value = Some(Value::Null);
         Ok(())
@@ -554,7 +640,7 @@ impl<'c> enc::ValueContainer for ValueContainer<'c> {
 
     fn encode_option<T: Encode>(&mut self, value: Option<&T>) -> Result<(), Self::Error> {
         let value = match value {
-            Some(v) => v.encode(&mut JsonEncoder::with_path(self.coding_path.clone()))?,
+            Some(v) => v.encode(&mut self.encoder())?,
             None => Value::Null,
         };
         self.value = Some(value);
@@ -562,7 +648,7 @@ impl<'c> enc::ValueContainer for ValueContainer<'c> {
     }
 
     fn encode<T: Encode>(&mut self, value: &T) -> Result<(), Self::Error> {
-        let value = value.encode(&mut JsonEncoder::with_path(self.coding_path.clone()))?;
+        let value = value.encode(&mut self.encoder())?;
         self.value = Some(value);
         Ok(())
     }
@@ -574,16 +660,28 @@ impl<'c> enc::ValueContainer for ValueContainer<'c> {
 
 pub struct SeqContainer<'a> {
     coding_path: CodingPath<'a>,
+    options: Arc<JsonOptions>,
     values: Vec<Value>,
 }
 
 impl<'en> SeqContainer<'en> {
-    pub fn new(coding_path: CodingPath<'en>) -> Self {
+    pub fn new(coding_path: CodingPath<'en>, options: Arc<JsonOptions>) -> Self {
         Self {
-            coding_path,
+            coding_path: coding_path.join_shared(CodingKey::Int(0)),
+            options,
             values: vec![],
         }
     }
+
+    fn encoder(&self) -> JsonEncoder<'en> {
+        JsonEncoder::with_path(self.coding_path.clone(), self.options.clone())
+    }
+
+    #[inline]
+    fn push(&mut self, value: Value) {
+        self.values.push(value);
+        self.coding_path.replace(CodingKey::Int(self.values.len()));
+    }
 }
 
 impl<'c> enc::SeqContainer for SeqContainer<'c> {
@@ -596,114 +694,119 @@ impl<'c> enc::SeqContainer for SeqContainer<'c> {
     }
 
     fn encode_u8(&mut self, value: u8) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_u16(&mut self, value: u16) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_u32(&mut self, value: u32) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_u64(&mut self, value: u64) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_u128(&mut self, value: u128) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_usize(&mut self, value: usize) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_i8(&mut self, value: i8) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_i16(&mut self, value: i16) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_i32(&mut self, value: i32) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_i64(&mut self, value: i64) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_i128(&mut self, value: i128) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_isize(&mut self, value: isize) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_str(&mut self, value: &str) -> Result<(), Self::Error> {
-        self.values.push(Value::String(value.to_string()));
+        self.push(Value::String(value.to_string()));
         Ok(())
     }
 
     fn encode_f32(&mut self, value: f32) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_f64(&mut self, value: f64) -> Result<(), Self::Error> {
-        self.values.push(Value::Number(value.to_string()));
+        self.push(Value::Number(value.to_string()));
         Ok(())
     }
 
     fn encode_bool(&mut self, value: bool) -> Result<(), Self::Error> {
-        self.values.push(Value::Bool(value));
+        self.push(Value::Bool(value));
+        Ok(())
+    }
+
+    fn encode_bytes(&mut self, value: &[u8]) -> Result<(), Self::Error> {
+        self.push(bytes::to_value(self.options.bytes, value));
         Ok(())
     }
 
     fn encode_null(&mut self) -> Result<(), Self::Error> {
-        self.values.push(Value::Null);
+        self.push(Value::Null);
         Ok(())
     }
 
     fn encode_option<T: Encode>(&mut self, value: Option<&T>) -> Result<(), Self::Error> {
         let value = match value {
-            Some(v) => v.encode(&mut JsonEncoder::with_path(self.coding_path.clone()))?,
+            Some(v) => v.encode(&mut self.encoder())?,
             None => Value::Null,
         };
-        self.values.push(value);
+        self.push(value);
         Ok(())
     }
 
     fn encode<T: Encode>(&mut self, value: &T) -> Result<(), Self::Error> {
-        let value = value.encode(&mut JsonEncoder::with_path(self.coding_path.clone()))?;
-        self.values.push(value);
+        let value = value.encode(&mut self.encoder())?;
+        self.push(value);
         Ok(())
     }
 
     fn nested_container<'a>(
         &'a mut self,
-    ) -> Result<<Self::Encoder<'a> as Encoder<'_>>::KeyedContainer, Self::Error> {
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::KeyedContainer, Self::Error> {
         todo!()
     }
 
     fn nested_seq_container<'a>(
         &'a mut self,
-    ) -> Result<<Self::Encoder<'a> as Encoder<'_>>::SeqContainer, Self::Error> {
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::SeqContainer, Self::Error> {
         todo!()
     }
 
@@ -878,10 +981,10 @@ mod test {
             }
         }
 
-        impl Decode for ThingA {
-            fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+        impl<'de> Decode<'de> for ThingA {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
             where
-                D: Decoder + 'd,
+                D: Decoder<'de>,
             {
                 let mut d = decoder.as_container()?;
                 Ok(ThingA {
@@ -891,10 +994,10 @@ mod test {
             }
         }
 
-        impl Decode for ThingB {
-            fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+        impl<'de> Decode<'de> for ThingB {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
             where
-                D: Decoder + 'd,
+                D: Decoder<'de>,
             {
                 let mut d = decoder.as_container()?;
                 Ok(ThingB {
@@ -904,10 +1007,10 @@ mod test {
             }
         }
 
-        impl Decode for Tagged {
-            fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+        impl<'de> Decode<'de> for Tagged {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
             where
-                D: Decoder + 'd,
+                D: Decoder<'de>,
             {
                 let mut d = decoder.as_container()?;
                 let tag = d.decode_string(&"tag")?;
@@ -920,10 +1023,10 @@ mod test {
             }
         }
 
-        impl Decode for Base {
-            fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+        impl<'de> Decode<'de> for Base {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
             where
-                D: Decoder + 'd,
+                D: Decoder<'de>,
             {
                 let mut d = decoder.as_container()?;
                 Ok(Base {
@@ -946,4 +1049,74 @@ mod test {
 
         println!("{:?}", &base);
     }
+
+    #[test]
+    fn send_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<JsonEncoder<'static>>();
+        assert_send_sync::<crate::enc::KeyedContainer<'static>>();
+        assert_send_sync::<crate::enc::ValueContainer<'static>>();
+        assert_send_sync::<crate::enc::SeqContainer<'static>>();
+        assert_send_sync::<crate::JsonDecoder<'static, 'static>>();
+        assert_send_sync::<crate::dec::KeyedContainer<'static, 'static>>();
+        assert_send_sync::<crate::dec::ValueContainer<'static, 'static>>();
+        assert_send_sync::<crate::dec::SeqContainer<'static, 'static>>();
+
+        let mut encoder = JsonEncoder::new();
+        let mut con = encoder.as_container();
+        let value = std::thread::spawn(move || {
+            con.encode(&vec![1u8, 2, 3], &"a").unwrap();
+            con.finish()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(
+            from_value::<HashMap<String, Vec<u8>>>(&value).unwrap()["a"],
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn custom_error() {
+        struct Percent(u8);
+
+        impl Encode for Percent {
+            fn encode<'e, E>(&self, encoder: &mut E) -> enc::EncodeResult<'e, E>
+            where
+                E: Encoder<'e>,
+            {
+                use codable::enc::ValueContainer as _;
+
+                let mut c = encoder.as_value_container();
+                if self.0 > 100 {
+                    return Err(c.custom_error(format!("{} is out of range", self.0)));
+                }
+                c.encode_u8(self.0)?;
+                Ok(c.finish())
+            }
+        }
+
+        let mut input = HashMap::new();
+        input.insert("volume", Percent(50));
+        assert!(to_value(&input).is_ok());
+
+        input.insert("balance", Percent(150));
+        assert_matches!(
+            to_value(&input),
+            Err(Error::Custom(coding_path, message, ..)) => {
+                assert_eq!(coding_path.to_string(), "balance");
+                assert_eq!(message, "150 is out of range");
+            }
+        );
+
+        let mut input = HashMap::new();
+        input.insert("levels", vec![Percent(1), Percent(2), Percent(200)]);
+        assert_matches!(
+            to_value(&input),
+            Err(Error::Custom(coding_path, ..)) => {
+                assert_eq!(coding_path.to_string(), "levels[2]");
+            }
+        );
+    }
 }
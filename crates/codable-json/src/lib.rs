@@ -1,9 +1,16 @@
 #![deny(rust_2018_idioms)]
 
+#[cfg(test)]
+#[macro_use]
+mod test_util;
+
+mod bytes;
 pub mod dec;
 pub mod enc;
+mod options;
 pub mod value;
 
 pub use dec::JsonDecoder;
 pub use enc::JsonEncoder;
-pub use value::{from_value, to_value, Value};
+pub use options::{BytesFormat, JsonOptions};
+pub use value::{from_value, from_value_with, to_value, to_value_with, Value};
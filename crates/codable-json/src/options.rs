@@ -0,0 +1,150 @@
+#[cfg(feature = "chrono")]
+use codable::date::{DateDecodingStrategy, DateEncodingStrategy};
+use codable::{dec::Limits, UserInfo};
+
+/// How byte strings written with `encode_bytes` are represented in JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BytesFormat {
+    /// A standard, padded base64 string.
+    #[default]
+    Base64,
+    /// A lowercase hex string.
+    Hex,
+    /// An array of numbers, one per byte.
+    Array,
+}
+
+/// Settings for a [`JsonEncoder`](crate::JsonEncoder) or
+/// [`JsonDecoder`](crate::JsonDecoder), shared with every nested encoder or
+/// decoder it creates.
+#[derive(Debug, Clone, Default)]
+pub struct JsonOptions {
+    pub bytes: BytesFormat,
+    /// Reports the format as not human-readable, so impls such as `Uuid` and
+    /// the chrono types use their compact representations.
+    pub compact: bool,
+    /// Keeps decoding past recoverable errors and reports them all at once as
+    /// [`Error::Multiple`](crate::dec::Error::Multiple).
+    pub collect_errors: bool,
+    /// Bounds on nesting and sizes when decoding untrusted input.
+    pub limits: Limits,
+    /// Values for `Encode` and `Decode` impls to read through
+    /// `Encoder::context` and `Decoder::context`.
+    pub user_info: UserInfo,
+    /// How the chrono impls write dates.
+    #[cfg(feature = "chrono")]
+    pub date_encoding: DateEncodingStrategy,
+    /// How the chrono impls read dates.
+    #[cfg(feature = "chrono")]
+    pub date_decoding: DateDecodingStrategy,
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod test {
+    use super::*;
+    use crate::{from_value_with, test_util::with_dates, to_value_with, Value};
+
+    #[test]
+    fn date_strategies() {
+        use std::sync::Arc;
+
+        use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+
+        let date_time = NaiveDate::from_ymd_opt(2023, 11, 14)
+            .unwrap()
+            .and_hms_milli_opt(22, 13, 20, 500)
+            .unwrap();
+
+        let cases = [
+            (
+                DateEncodingStrategy::SecondsSinceEpoch,
+                DateDecodingStrategy::SecondsSinceEpoch,
+                Value::Number("1700000000.5".into()),
+            ),
+            (
+                DateEncodingStrategy::MillisecondsSinceEpoch,
+                DateDecodingStrategy::MillisecondsSinceEpoch,
+                Value::Number("1700000000500".into()),
+            ),
+            (
+                DateEncodingStrategy::MicrosecondsSinceEpoch,
+                DateDecodingStrategy::MicrosecondsSinceEpoch,
+                Value::Number("1700000000500000".into()),
+            ),
+            (
+                DateEncodingStrategy::Formatted("%d/%m/%Y %H:%M:%S%.3f".into()),
+                DateDecodingStrategy::Formatted("%d/%m/%Y %H:%M:%S%.3f".into()),
+                Value::String("14/11/2023 22:13:20.500".into()),
+            ),
+        ];
+        for (encoding, decoding, expected) in cases {
+            let options = with_dates(encoding, decoding);
+            let value = to_value_with(&date_time, options.clone()).unwrap();
+            assert_eq!(value, expected);
+            assert_eq!(
+                from_value_with::<NaiveDateTime>(&value, options.clone()).unwrap(),
+                date_time
+            );
+            assert_eq!(to_value_with(&date_time.and_utc(), options).unwrap(), value);
+        }
+
+        // ISO 8601 wins over the compact form.
+        let options = JsonOptions {
+            compact: true,
+            ..with_dates(DateEncodingStrategy::Iso8601, DateDecodingStrategy::Iso8601)
+        };
+        let date = date_time.date();
+        let value = to_value_with(&date, options.clone()).unwrap();
+        assert_eq!(value, Value::String("2023-11-14".into()));
+        assert_eq!(from_value_with::<NaiveDate>(&value, options).unwrap(), date);
+
+        // Dates are their midnight in UTC.
+        let options = with_dates(
+            DateEncodingStrategy::SecondsSinceEpoch,
+            DateDecodingStrategy::SecondsSinceEpoch,
+        );
+        let value = to_value_with(&date, options.clone()).unwrap();
+        assert_eq!(value, Value::Number("1699920000".into()));
+        assert_eq!(from_value_with::<NaiveDate>(&value, options).unwrap(), date);
+
+        let options = with_dates(
+            DateEncodingStrategy::Custom(Arc::new(|v: &DateTime<FixedOffset>| {
+                format!("@{}", v.timestamp())
+            })),
+            DateDecodingStrategy::Custom(Arc::new(|s: &str| {
+                let secs = s.strip_prefix('@').ok_or("missing `@`")?;
+                let secs = secs.parse().map_err(|_| "not a number")?;
+                let v = DateTime::<Utc>::from_timestamp(secs, 0).ok_or("out of range")?;
+                Ok(v.fixed_offset())
+            })),
+        );
+        let value = to_value_with(&date, options.clone()).unwrap();
+        assert_eq!(value, Value::String("@1699920000".into()));
+        assert_eq!(
+            from_value_with::<NaiveDate>(&value, options.clone()).unwrap(),
+            date
+        );
+        assert_eq!(
+            from_value_with::<NaiveDate>(&Value::String("1699920000".into()), options)
+                .unwrap_err()
+                .to_string(),
+            "<root>: missing `@`"
+        );
+
+        // Formats asking for fields the value lacks fail instead of panicking.
+        let options = with_dates(
+            DateEncodingStrategy::Formatted("%H:%M".into()),
+            DateDecodingStrategy::Default,
+        );
+        assert!(to_value_with(&date, options).is_err());
+
+        let options = with_dates(
+            DateEncodingStrategy::Default,
+            DateDecodingStrategy::MillisecondsSinceEpoch,
+        );
+        assert!(
+            from_value_with::<NaiveDateTime>(&Value::Number(i64::MAX.to_string()), options)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! Helpers shared by the tests in this crate.
+
+#[cfg(feature = "chrono")]
+use codable::date::{DateDecodingStrategy, DateEncodingStrategy};
+use codable::dec::Limits;
+
+use crate::JsonOptions;
+
+/// Asserts that a value matches a pattern, and evaluates to the expression
+/// after `=>`, which can use the pattern's bindings.
+macro_rules! assert_matches {
+    ($value:expr, $pattern:pat $(if $guard:expr)? $(=> $then:expr)? $(,)?) => {
+        match $value {
+            $pattern $(if $guard)? => { $($then)? }
+            x => panic!("unexpected result: {x:?}"),
+        }
+    };
+}
+
+/// Options for the compact representations of types such as `Uuid` and the
+/// chrono types.
+pub(crate) fn compact() -> JsonOptions {
+    JsonOptions {
+        compact: true,
+        ..Default::default()
+    }
+}
+
+/// Options that collect every recoverable error instead of stopping at the
+/// first.
+pub(crate) fn collecting() -> JsonOptions {
+    JsonOptions {
+        collect_errors: true,
+        ..Default::default()
+    }
+}
+
+pub(crate) fn with_limits(limits: Limits) -> JsonOptions {
+    JsonOptions {
+        limits,
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub(crate) fn with_dates(
+    date_encoding: DateEncodingStrategy,
+    date_decoding: DateDecodingStrategy,
+) -> JsonOptions {
+    JsonOptions {
+        date_encoding,
+        date_decoding,
+        ..Default::default()
+    }
+}
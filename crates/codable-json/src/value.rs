@@ -3,66 +3,87 @@ use std::str::FromStr;
 use indexmap::IndexMap;
 
 use codable::{
-    dec::{Decode, DecodeResult, Decoder, ValueContainer as _},
+    dec::{CustomError as _, Decode, DecodeResult, Decoder, ValueContainer as _, ValueKind},
     enc::{Encode, ValueContainer as _},
     CodingPath,
 };
 
 use crate::{
     dec::{self, Error},
-    enc, JsonDecoder, JsonEncoder,
+    enc, JsonDecoder, JsonEncoder, JsonOptions,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum Value {
     String(String),
     Number(String),
     Bool(bool),
+    #[default]
     Null,
     Array(Vec<Value>),
     Object(IndexMap<String, Value>),
 }
 
-impl Default for Value {
-    fn default() -> Self {
-        Self::Null
-    }
+#[inline(always)]
+pub fn to_value<T: Encode>(input: &T) -> Result<Value, enc::Error> {
+    to_value_with(input, JsonOptions::default())
 }
 
 #[inline(always)]
-pub fn to_value<T: Encode>(input: &T) -> Result<Value, enc::Error> {
-    let mut encoder = JsonEncoder::with_path(CodingPath::root());
+pub fn to_value_with<T: Encode>(input: &T, options: JsonOptions) -> Result<Value, enc::Error> {
+    let mut encoder = JsonEncoder::with_options(options);
     input.encode(&mut encoder)
 }
 
 #[inline(always)]
-pub fn from_value<T: Decode>(input: &Value) -> Result<T, dec::Error> {
-    let mut decoder = JsonDecoder::new(CodingPath::root(), input);
-    T::decode(&mut decoder)
+pub fn from_value<'a, T: Decode<'a>>(input: &'a Value) -> Result<T, dec::Error> {
+    from_value_with(input, JsonOptions::default())
+}
+
+#[inline(always)]
+pub fn from_value_with<'a, T: Decode<'a>>(
+    input: &'a Value,
+    options: JsonOptions,
+) -> Result<T, dec::Error> {
+    let mut decoder = JsonDecoder::with_options(input, options);
+    decoder.decode()
 }
 
 impl Value {
     pub fn is_scalar(&self) -> bool {
+        !matches!(self, Value::Array(_) | Value::Object(_))
+    }
+
+    pub(crate) fn kind(&self) -> &'static str {
         match self {
-            Value::Array(_) | Value::Object(_) => false,
-            _ => true,
+            Value::String(_) => "string",
+            Value::Number(_) => "number",
+            Value::Bool(_) => "bool",
+            Value::Null => "null",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
         }
     }
 
     pub fn as_map(&self, coding_path: &CodingPath<'_>) -> Result<&IndexMap<String, Value>, Error> {
         match self {
             Value::Object(ref x) => Ok(x),
-            x => Err(Error::InvalidType(format!(
-                "{coding_path} (as_map) {:?}",
-                x
-            ))),
+            x => Err(Error::invalid_type(
+                coding_path.to_path_buf(),
+                "object",
+                x.kind(),
+            )),
         }
     }
 
     pub fn as_array(&self, coding_path: &CodingPath<'_>) -> Result<&Vec<Value>, Error> {
         match self {
             Value::Array(ref x) => Ok(x),
-            _ => Err(Error::InvalidType(coding_path.to_string())),
+            x => Err(Error::invalid_type(
+                coding_path.to_path_buf(),
+                "array",
+                x.kind(),
+            )),
         }
     }
 }
@@ -87,39 +108,45 @@ impl Encode for Value {
     }
 }
 
-impl Decode for Value {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+impl<'de> Decode<'de> for Value {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
         Self: Sized,
-        D: Decoder + 'd,
+        D: Decoder<'de>,
     {
-        if let Ok(mut d) = decoder.as_value_container() {
-            if let Ok(x) = d.decode_f64() {
-                return Ok(Value::Number(x.to_string()));
-            }
-
-            if let Ok(x) = d.decode_string() {
-                return Ok(Value::String(x));
+        let value = match decoder.peek_kind()? {
+            ValueKind::Null => {
+                decoder.as_value_container()?.decode_null()?;
+                Value::Null
             }
-
-            if let Ok(x) = d.decode_bool() {
-                return Ok(Value::Bool(x));
+            ValueKind::Bool => Value::Bool(decoder.as_value_container()?.decode_bool()?),
+            ValueKind::Int => {
+                let mut d = decoder.as_value_container()?;
+                // Integers beyond `i128::MAX` are still valid `u128`s.
+                match d.decode_i128() {
+                    Ok(x) => Value::Number(x.to_string()),
+                    Err(_) => Value::Number(d.decode_u128()?.to_string()),
+                }
             }
-
-            if let Ok(_) = d.decode_null() {
-                return Ok(Value::Null);
-            }
-        }
-
-        if let Ok(_) = decoder.as_container() {
-            return Ok(Value::Object(Decode::decode(decoder)?));
-        }
-
-        if let Ok(_) = decoder.as_seq_container() {
-            return Ok(Value::Array(Decode::decode(decoder)?));
-        }
-
-        panic!("Invalid type");
+            ValueKind::Float => Value::Number(
+                decoder
+                    .as_value_container()?
+                    .decode_number_str()?
+                    .into_owned(),
+            ),
+            ValueKind::String => Value::String(decoder.as_value_container()?.decode_string()?),
+            ValueKind::Bytes => Value::Array(
+                decoder
+                    .as_value_container()?
+                    .decode_bytes()?
+                    .iter()
+                    .map(|x| Value::Number(x.to_string()))
+                    .collect(),
+            ),
+            ValueKind::Seq => Value::Array(Decode::decode(decoder)?),
+            ValueKind::Map => Value::Object(Decode::decode(decoder)?),
+        };
+        Ok(value)
     }
 }
 
@@ -162,6 +189,7 @@ mod test {
     use codable::enc::KeyedContainer;
 
     use super::*;
+    use crate::test_util::compact;
 
     #[test]
     fn basic_int() {
@@ -192,4 +220,848 @@ mod test {
         let value: crate::Value = from_value(&value).unwrap();
         println!("{:?}", value);
     }
+
+    #[test]
+    fn smart_pointers() {
+        use std::{
+            cell::{Cell, RefCell},
+            cmp::Reverse,
+            marker::PhantomData,
+            num::{Saturating, Wrapping},
+            rc::Rc,
+            sync::Arc,
+        };
+
+        #[derive(Debug, PartialEq)]
+        enum Expr {
+            Num(u32),
+            Neg(Box<Expr>),
+        }
+
+        impl Encode for Expr {
+            fn encode<'e, E>(&self, encoder: &mut E) -> codable::enc::EncodeResult<'e, E>
+            where
+                E: codable::enc::Encoder<'e>,
+            {
+                let mut c = encoder.as_container();
+                match self {
+                    Expr::Num(x) => c.encode(x, &"num")?,
+                    Expr::Neg(x) => c.encode(x, &"neg")?,
+                }
+                Ok(c.finish())
+            }
+        }
+
+        impl<'de> Decode<'de> for Expr {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                use codable::dec::KeyedContainer as _;
+
+                let mut c = decoder.as_container()?;
+                if c.contains(&"neg") {
+                    Ok(Expr::Neg(c.decode(&"neg")?))
+                } else {
+                    Ok(Expr::Num(c.decode(&"num")?))
+                }
+            }
+        }
+
+        let expr = Expr::Neg(Box::new(Expr::Neg(Box::new(Expr::Num(7)))));
+        let value = to_value(&expr).unwrap();
+        assert_eq!(from_value::<Expr>(&value).unwrap(), expr);
+
+        let value = to_value(&Rc::<str>::from("shared")).unwrap();
+        assert_eq!(value, Value::String("shared".into()));
+        assert_eq!(&*from_value::<Arc<str>>(&value).unwrap(), "shared");
+        assert_eq!(&*from_value::<Box<str>>(&value).unwrap(), "shared");
+
+        let value = to_value(&Arc::<[u16]>::from(vec![1, 2])).unwrap();
+        assert_eq!(&*from_value::<Box<[u16]>>(&value).unwrap(), &[1, 2]);
+        assert_eq!(&*from_value::<Rc<[u16]>>(&value).unwrap(), &[1, 2]);
+
+        let value = to_value(&Cell::new(3u8)).unwrap();
+        assert_eq!(from_value::<RefCell<u8>>(&value).unwrap().into_inner(), 3);
+        assert_eq!(from_value::<Cell<u8>>(&value).unwrap().get(), 3);
+        assert_eq!(from_value::<Reverse<u8>>(&value).unwrap(), Reverse(3));
+        assert_eq!(from_value::<Wrapping<u8>>(&value).unwrap(), Wrapping(3));
+        assert_eq!(from_value::<Saturating<u8>>(&value).unwrap(), Saturating(3));
+        assert_eq!(to_value(&Wrapping(3u8)).unwrap(), value);
+
+        let cell = RefCell::new(1u8);
+        let _guard = cell.borrow_mut();
+        assert!(matches!(to_value(&cell), Err(enc::Error::Custom(..))));
+
+        assert_eq!(to_value(&PhantomData::<String>).unwrap(), Value::Null);
+        assert_eq!(
+            from_value::<PhantomData<String>>(&Value::Null).unwrap(),
+            PhantomData
+        );
+    }
+
+    #[test]
+    fn tuples_and_arrays() {
+        let value = to_value(&(1u8, "two".to_string(), true)).unwrap();
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::Number("1".into()),
+                Value::String("two".into()),
+                Value::Bool(true)
+            ])
+        );
+        assert_eq!(
+            from_value::<(u8, String, bool)>(&value).unwrap(),
+            (1, "two".to_string(), true)
+        );
+
+        let value = to_value(&[[1u8, 2], [3, 4]]).unwrap();
+        assert_eq!(
+            from_value::<[[u8; 2]; 2]>(&value).unwrap(),
+            [[1, 2], [3, 4]]
+        );
+        assert_eq!(
+            from_value::<[(u8, u8); 2]>(&value).unwrap(),
+            [(1, 2), (3, 4)]
+        );
+
+        assert_matches!(
+            from_value::<[[u8; 3]; 2]>(&value),
+            Err(dec::Error::InvalidLength {
+                coding_path,
+                len,
+                expected,
+                ..
+            }) => {
+                assert_eq!(coding_path.to_string(), "[0]");
+                assert_eq!(len, 2);
+                assert_eq!(expected, "an array of length 3");
+            }
+        );
+
+        assert_matches!(
+            from_value::<(u8, u8, u8)>(&value),
+            Err(dec::Error::InvalidLength { len, expected, .. }) => {
+                assert_eq!(len, 2);
+                assert_eq!(expected, "a tuple of size 3");
+            }
+        );
+    }
+
+    #[test]
+    fn collections() {
+        use std::collections::{BTreeSet, BinaryHeap, HashSet, LinkedList, VecDeque};
+
+        let value = to_value(&VecDeque::from([3u8, 1, 2])).unwrap();
+        assert_eq!(
+            from_value::<VecDeque<u8>>(&value).unwrap(),
+            VecDeque::from([3, 1, 2])
+        );
+        assert_eq!(
+            from_value::<LinkedList<u8>>(&value).unwrap(),
+            LinkedList::from([3, 1, 2])
+        );
+        assert_eq!(
+            from_value::<BinaryHeap<u8>>(&value)
+                .unwrap()
+                .into_sorted_vec(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            from_value::<BTreeSet<u8>>(&value).unwrap(),
+            BTreeSet::from([1, 2, 3])
+        );
+        assert_eq!(
+            from_value::<HashSet<u8>>(&value).unwrap(),
+            HashSet::from([1, 2, 3])
+        );
+        assert_eq!(
+            from_value::<indexmap::IndexSet<u8>>(&value)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![3, 1, 2]
+        );
+
+        // Any hasher will do for the indexmap types.
+        type Hasher = std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+        let set = from_value::<indexmap::IndexSet<u8, Hasher>>(&value).unwrap();
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![3, 1, 2]);
+        assert_eq!(to_value(&set).unwrap(), value);
+        let mut map = IndexMap::<String, u8, Hasher>::default();
+        map.insert("b".into(), 2);
+        map.insert("a".into(), 1);
+        let decoded = from_value::<IndexMap<String, u8, Hasher>>(&to_value(&map).unwrap());
+        assert_eq!(decoded.unwrap(), map);
+
+        let value = to_value(&LinkedList::from([vec![1u8], vec![2, 3, 2]])).unwrap();
+        assert_matches!(
+            from_value::<Vec<BTreeSet<u8>>>(&value),
+            Err(dec::Error::Custom(coding_path, message, ..)) => {
+                assert_eq!(coding_path.to_string(), "[1][2]");
+                assert_eq!(message, "duplicate set element");
+            }
+        );
+    }
+
+    #[test]
+    fn non_string_map_keys() {
+        use std::collections::BTreeMap;
+
+        let value = Value::Object(IndexMap::from([
+            ("2".to_string(), Value::Bool(false)),
+            ("1".to_string(), Value::Bool(true)),
+        ]));
+        assert_eq!(
+            from_value::<BTreeMap<u8, bool>>(&value).unwrap(),
+            BTreeMap::from([(1, true), (2, false)])
+        );
+
+        let value = Value::Object(IndexMap::from([(
+            "levels".to_string(),
+            Value::Object(IndexMap::from([("x".to_string(), Value::Bool(true))])),
+        )]));
+        assert_matches!(
+            from_value::<BTreeMap<String, BTreeMap<char, bool>>>(&value),
+            Ok(map) => assert!(map["levels"][&'x']),
+        );
+        assert_matches!(
+            from_value::<BTreeMap<String, BTreeMap<u8, bool>>>(&value),
+            Err(dec::Error::Custom(coding_path, message, ..)) => {
+                assert_eq!(coding_path.to_string(), "levels.x");
+                assert_eq!(message, "invalid key `x`, expected an integer of type u8");
+            }
+        );
+    }
+
+    #[test]
+    fn byte_strings() {
+        use std::collections::BTreeMap;
+
+        use codable::{ByteBuf, Bytes};
+
+        use crate::BytesFormat;
+
+        let raw = ByteBuf::from(b"hi\xff".to_vec());
+        let with = |bytes| JsonOptions {
+            bytes,
+            ..Default::default()
+        };
+
+        let value = to_value(&raw).unwrap();
+        assert_eq!(value, Value::String("aGn/".into()));
+        assert_eq!(from_value::<ByteBuf>(&value).unwrap(), raw);
+
+        let value = to_value_with(&vec![raw.clone()], with(BytesFormat::Hex)).unwrap();
+        assert_eq!(value, Value::Array(vec![Value::String("6869ff".into())]));
+        assert_eq!(
+            from_value_with::<Vec<ByteBuf>>(&value, with(BytesFormat::Hex)).unwrap(),
+            vec![raw.clone()]
+        );
+
+        let value = to_value_with(&Bytes::new(&raw), with(BytesFormat::Array)).unwrap();
+        assert_eq!(value, to_value(&raw.to_vec()).unwrap());
+        assert_eq!(
+            from_value_with::<ByteBuf>(&value, with(BytesFormat::Array)).unwrap(),
+            raw
+        );
+        assert!(from_value_with::<Bytes<'_>>(&value, with(BytesFormat::Array)).is_err());
+        assert!(from_value_with::<&[u8]>(&value, with(BytesFormat::Array)).is_err());
+
+        // The elements of a byte array have their errors collected too.
+        let number = |x: &str| Value::Number(x.into());
+        let value = Value::Array(vec![number("1"), Value::Bool(true), number("300")]);
+        let options = JsonOptions {
+            collect_errors: true,
+            ..with(BytesFormat::Array)
+        };
+        let error = from_value_with::<ByteBuf>(&value, options).unwrap_err();
+        let paths = error
+            .errors()
+            .iter()
+            .map(|x| x.coding_path().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["[1]", "[2]"]);
+
+        let map = BTreeMap::from([("a".to_string(), raw.clone())]);
+        let value = to_value(&map).unwrap();
+        assert_eq!(
+            value,
+            Value::Object(IndexMap::from([("a".into(), Value::String("aGn/".into()))]))
+        );
+        assert_eq!(
+            from_value::<BTreeMap<String, ByteBuf>>(&value).unwrap(),
+            map
+        );
+
+        let value = Value::Array(vec![Value::String("not base64".into())]);
+        assert_matches!(
+            from_value::<Vec<ByteBuf>>(&value),
+            Err(dec::Error::InvalidType {
+                coding_path,
+                expected,
+                ..
+            }) => {
+                assert_eq!(coding_path.to_string(), "[0]");
+                assert_eq!(expected, "a base64 string");
+            }
+        );
+    }
+
+    #[test]
+    fn chars_and_unit() {
+        let value = to_value(&['a', 'é', '🦀']).unwrap();
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::String("a".into()),
+                Value::String("é".into()),
+                Value::String("🦀".into())
+            ])
+        );
+        assert_eq!(from_value::<[char; 3]>(&value).unwrap(), ['a', 'é', '🦀']);
+
+        let value = Value::Array(vec![Value::String("a".into()), Value::String("bc".into())]);
+        assert_matches!(
+            from_value::<Vec<char>>(&value),
+            Err(dec::Error::InvalidLength {
+                coding_path,
+                len,
+                expected,
+                ..
+            }) => {
+                assert_eq!(coding_path.to_string(), "[1]");
+                assert_eq!(len, 2);
+                assert_eq!(expected, "a single character");
+            }
+        );
+        assert!(from_value::<char>(&Value::String("".into())).is_err());
+
+        assert_eq!(to_value(&()).unwrap(), Value::Null);
+        assert_eq!(from_value::<()>(&Value::Null).unwrap(), ());
+        assert!(from_value::<()>(&Value::Bool(false)).is_err());
+    }
+
+    #[test]
+    fn peek_kind() {
+        use codable::dec::Decoder as _;
+
+        let value = Value::Object(IndexMap::from([
+            ("int".to_string(), Value::Number(u128::MAX.to_string())),
+            ("neg".to_string(), Value::Number("-3".into())),
+            ("float".to_string(), Value::Number("1.5".into())),
+            ("string".to_string(), Value::String("1".into())),
+            ("bool".to_string(), Value::Bool(false)),
+            ("null".to_string(), Value::Null),
+            ("seq".to_string(), Value::Array(vec![Value::Null])),
+        ]));
+
+        let kinds = [
+            ValueKind::Int,
+            ValueKind::Int,
+            ValueKind::Float,
+            ValueKind::String,
+            ValueKind::Bool,
+            ValueKind::Null,
+            ValueKind::Seq,
+        ];
+        let Value::Object(map) = &value else {
+            unreachable!()
+        };
+        for (v, kind) in map.values().zip(kinds) {
+            assert_eq!(
+                JsonDecoder::with_options(v, Default::default())
+                    .peek_kind()
+                    .unwrap(),
+                kind
+            );
+        }
+        assert_eq!(
+            JsonDecoder::with_options(&value, Default::default())
+                .peek_kind()
+                .unwrap(),
+            ValueKind::Map
+        );
+
+        assert_eq!(from_value::<Value>(&value).unwrap(), value);
+
+        // Floats keep the text they were written with, not what `f64` makes of it.
+        for x in [
+            "1.10",
+            "0.1000000000000000055511151231257827",
+            "1e400",
+            "-0.0",
+        ] {
+            let value = Value::Number(x.into());
+            assert_eq!(from_value::<Value>(&value).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn next_element() {
+        use codable::dec::{Decode, DecodeResult, Decoder, SeqContainer};
+
+        #[derive(Debug, PartialEq)]
+        struct Sum {
+            hint: Option<usize>,
+            total: u32,
+        }
+
+        impl<'de> Decode<'de> for Sum {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                let mut con = decoder.as_seq_container()?;
+                let hint = con.size_hint();
+                let mut total = 0;
+                while let Some(n) = con.next_element::<u32>()? {
+                    total += n;
+                }
+                assert!(con.is_at_end()?);
+                Ok(Sum { hint, total })
+            }
+        }
+
+        let value = to_value(&vec![1u32, 2, 3]).unwrap();
+        assert_eq!(
+            from_value::<Sum>(&value).unwrap(),
+            Sum {
+                hint: Some(3),
+                total: 6
+            }
+        );
+        assert_eq!(
+            from_value::<Sum>(&Value::Array(vec![])).unwrap(),
+            Sum {
+                hint: Some(0),
+                total: 0
+            }
+        );
+    }
+
+    #[test]
+    fn user_info() {
+        use std::collections::BTreeMap;
+
+        use codable::{
+            dec::{Decode, DecodeResult, Decoder, ValueContainer as _},
+            enc::{Encoder, ValueContainer as _},
+            UserInfo,
+        };
+
+        struct Redact;
+
+        #[derive(Debug, PartialEq)]
+        struct Secret(String);
+
+        impl Encode for Secret {
+            fn encode<'e, E>(&self, encoder: &mut E) -> codable::enc::EncodeResult<'e, E>
+            where
+                E: Encoder<'e>,
+            {
+                let redact = encoder.context::<Redact>().is_some();
+                let mut c = encoder.as_value_container();
+                c.encode_str(if redact { "***" } else { &self.0 })?;
+                Ok(c.finish())
+            }
+        }
+
+        struct Prefix(&'static str);
+
+        impl<'de> Decode<'de> for Secret {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                let prefix = decoder.context::<Prefix>().map_or("", |p| p.0);
+                let value = decoder.as_value_container()?.decode_string()?;
+                Ok(Secret(format!("{prefix}{value}")))
+            }
+        }
+
+        // The store reaches impls nested inside maps and sequences.
+        let input = BTreeMap::from([("a", vec![Secret("hunter2".into())])]);
+        let plain = to_value(&input).unwrap();
+        let redacted = to_value_with(
+            &input,
+            JsonOptions {
+                user_info: UserInfo::new().with(Redact),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let strings = |value| from_value::<BTreeMap<String, Vec<String>>>(value).unwrap();
+        assert_eq!(strings(&plain)["a"], ["hunter2"]);
+        assert_eq!(strings(&redacted)["a"], ["***"]);
+
+        let decoded: BTreeMap<String, Vec<Secret>> = from_value_with(
+            &plain,
+            JsonOptions {
+                user_info: UserInfo::new().with(Prefix("k:")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(decoded["a"], vec![Secret("k:hunter2".into())]);
+    }
+
+    #[test]
+    fn human_readable() {
+        use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+        let id = uuid::Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+        assert_eq!(
+            to_value(&id).unwrap(),
+            Value::String("01234567-89ab-cdef-0123-456789abcdef".into())
+        );
+        let value = to_value_with(&id, compact()).unwrap();
+        assert_eq!(value, Value::String("ASNFZ4mrze8BI0VniavN7w==".into()));
+        assert_eq!(
+            from_value_with::<uuid::Uuid>(&value, compact()).unwrap(),
+            id
+        );
+
+        let date = NaiveDate::from_ymd_opt(1969, 12, 31).unwrap();
+        let value = to_value_with(&date, compact()).unwrap();
+        assert_eq!(value, Value::Number("-1".into()));
+        assert_eq!(
+            from_value_with::<NaiveDate>(&value, compact()).unwrap(),
+            date
+        );
+
+        let time = NaiveTime::from_hms_micro_opt(0, 0, 1, 500).unwrap();
+        let value = to_value_with(&time, compact()).unwrap();
+        assert_eq!(value, Value::Number("1000500".into()));
+        assert_eq!(
+            from_value_with::<NaiveTime>(&value, compact()).unwrap(),
+            time
+        );
+
+        let date_time = NaiveDateTime::new(NaiveDate::from_ymd_opt(1970, 1, 2).unwrap(), time);
+        let value = to_value_with(&date_time, compact()).unwrap();
+        assert_eq!(value, Value::Number("86401000500".into()));
+        assert_eq!(
+            from_value_with::<NaiveDateTime>(&value, compact()).unwrap(),
+            date_time
+        );
+        assert_eq!(
+            to_value_with(&date_time.and_utc(), compact()).unwrap(),
+            value
+        );
+
+        assert!(
+            from_value_with::<NaiveTime>(&Value::Number("86400000000".into()), compact()).is_err()
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_types() {
+        use chrono::{
+            DateTime, FixedOffset, Local, Month, NaiveDateTime, NaiveTime, TimeDelta, Timelike,
+            Utc, Weekday,
+        };
+        use codable::date::{DateDecodingStrategy, DateEncodingStrategy};
+
+        let string = |s: &str| Value::String(s.into());
+
+        let utc = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_000).unwrap();
+        let value = to_value(&utc).unwrap();
+        assert_eq!(value, string("2023-11-14T22:13:20.123456Z"));
+        assert_eq!(from_value::<DateTime<Utc>>(&value).unwrap(), utc);
+        assert_eq!(from_value::<DateTime<Local>>(&value).unwrap(), utc);
+        let value = to_value_with(&utc, compact()).unwrap();
+        assert_eq!(
+            from_value_with::<DateTime<Utc>>(&value, compact()).unwrap(),
+            utc
+        );
+
+        // Compact output drops what is below a microsecond, text keeps it.
+        let nanos = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+        let value = to_value_with(&nanos, compact()).unwrap();
+        assert_eq!(value, Value::Number("1700000000123456".into()));
+        assert_eq!(
+            from_value_with::<DateTime<Utc>>(&value, compact()).unwrap(),
+            utc
+        );
+        let value = to_value(&nanos).unwrap();
+        assert_eq!(value, string("2023-11-14T22:13:20.123456789Z"));
+        assert_eq!(from_value::<DateTime<Utc>>(&value).unwrap(), nanos);
+
+        let offset = utc.with_timezone(&FixedOffset::east_opt(5400).unwrap());
+        let value = to_value(&offset).unwrap();
+        assert_eq!(value, string("2023-11-14T23:43:20.123456+01:30"));
+        let decoded = from_value::<DateTime<FixedOffset>>(&value).unwrap();
+        assert_eq!(decoded.offset(), offset.offset());
+        assert_eq!(decoded, offset);
+
+        // Every fractional-second precision parses.
+        for (text, nanos) in [
+            ("", 0),
+            (".1", 100_000_000),
+            (".123", 123_000_000),
+            (".1234567", 123_456_700),
+            (".123456789", 123_456_789),
+        ] {
+            let date_time =
+                from_value::<DateTime<Utc>>(&string(&format!("2023-11-14T22:13:20{text}Z")))
+                    .unwrap();
+            assert_eq!(date_time.timestamp_subsec_nanos(), nanos);
+            let naive = from_value::<NaiveDateTime>(&string(&format!("2023-11-14T22:13:20{text}")))
+                .unwrap();
+            assert_eq!(naive, date_time.naive_utc());
+            let time = from_value::<NaiveTime>(&string(&format!("22:13:20{text}"))).unwrap();
+            assert_eq!(time, date_time.time());
+        }
+
+        // A format without an offset reads as UTC.
+        let options = JsonOptions {
+            date_encoding: DateEncodingStrategy::Formatted("%Y-%m-%d %H:%M:%S".into()),
+            date_decoding: DateDecodingStrategy::Formatted("%Y-%m-%d %H:%M:%S".into()),
+            ..Default::default()
+        };
+        let utc = utc.with_nanosecond(0).unwrap();
+        let value = to_value_with(&utc, options.clone()).unwrap();
+        assert_eq!(value, string("2023-11-14 22:13:20"));
+        assert_eq!(
+            from_value_with::<DateTime<FixedOffset>>(&value, options).unwrap(),
+            utc
+        );
+
+        for (delta, text, nanos) in [
+            (TimeDelta::zero(), "P0D", "0"),
+            (TimeDelta::milliseconds(90_500), "PT90.5S", "90500000000"),
+            (-TimeDelta::nanoseconds(1), "-PT0.000000001S", "-1"),
+        ] {
+            let value = to_value(&delta).unwrap();
+            assert_eq!(value, string(text));
+            assert_eq!(from_value::<TimeDelta>(&value).unwrap(), delta);
+            let value = to_value_with(&delta, compact()).unwrap();
+            assert_eq!(value, Value::Number(nanos.into()));
+            assert_eq!(
+                from_value_with::<TimeDelta>(&value, compact()).unwrap(),
+                delta
+            );
+        }
+        assert!(from_value::<TimeDelta>(&string("PT1.S1")).is_err());
+        assert!(to_value_with(&TimeDelta::MAX, compact()).is_err());
+
+        assert_eq!(to_value(&Weekday::Wed).unwrap(), string("Wed"));
+        assert_eq!(
+            from_value::<Weekday>(&string("wednesday")).unwrap(),
+            Weekday::Wed
+        );
+        let value = to_value_with(&Weekday::Wed, compact()).unwrap();
+        assert_eq!(value, Value::Number("2".into()));
+        assert_eq!(
+            from_value_with::<Weekday>(&value, compact()).unwrap(),
+            Weekday::Wed
+        );
+
+        assert_eq!(to_value(&Month::March).unwrap(), string("March"));
+        assert_eq!(from_value::<Month>(&string("mar")).unwrap(), Month::March);
+        let value = to_value_with(&Month::March, compact()).unwrap();
+        assert_eq!(value, Value::Number("3".into()));
+        assert_eq!(
+            from_value_with::<Month>(&value, compact()).unwrap(),
+            Month::March
+        );
+        assert!(from_value_with::<Month>(&Value::Number("13".into()), compact()).is_err());
+    }
+
+    #[test]
+    fn trait_objects() {
+        use std::{any::Any, collections::BTreeMap};
+
+        use codable::{Decode, Encode};
+
+        trait Plugin: Any {
+            fn name(&self) -> String;
+        }
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        struct Http {
+            url: String,
+        }
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        struct Retry {
+            attempts: u8,
+        }
+
+        #[derive(Debug)]
+        struct Unlisted;
+
+        impl Plugin for Http {
+            fn name(&self) -> String {
+                format!("http {}", self.url)
+            }
+        }
+
+        impl Plugin for Retry {
+            fn name(&self) -> String {
+                format!("retry {}", self.attempts)
+            }
+        }
+
+        impl Plugin for Unlisted {
+            fn name(&self) -> String {
+                "unlisted".into()
+            }
+        }
+
+        codable::registry! {
+            dyn Plugin {
+                Http => "http",
+                Retry => "retry",
+            }
+        }
+
+        let plugins: Vec<Box<dyn Plugin>> = vec![
+            Box::new(Http {
+                url: "https://example.com".into(),
+            }),
+            Box::new(Retry { attempts: 3 }),
+        ];
+        let value = to_value(&plugins).unwrap();
+        let Value::Array(items) = &value else {
+            unreachable!()
+        };
+        let retry = from_value::<BTreeMap<String, Value>>(&items[1]).unwrap();
+        assert_eq!(retry["type"], Value::String("retry".into()));
+        assert_eq!(
+            retry["value"],
+            Value::Object(IndexMap::from([(
+                "attempts".into(),
+                Value::Number("3".into())
+            )]))
+        );
+
+        let decoded = from_value::<Vec<Box<dyn Plugin>>>(&value).unwrap();
+        let names = decoded.iter().map(|x| x.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["http https://example.com", "retry 3"]);
+
+        let error = to_value(&(Box::new(Unlisted) as Box<dyn Plugin>)).unwrap_err();
+        assert!(format!("{error:?}").contains("not registered for `dyn Plugin`"));
+
+        let mut map = IndexMap::new();
+        map.insert("type".to_string(), Value::String("ftp".into()));
+        match from_value::<Box<dyn Plugin>>(&Value::Object(map)) {
+            Err(dec::Error::UnknownVariant {
+                coding_path,
+                variant,
+                expected,
+                ..
+            }) => {
+                assert_eq!(coding_path.to_string(), "type");
+                assert_eq!(variant, "ftp");
+                assert_eq!(expected, ["http", "retry"]);
+            }
+            Err(error) => panic!("unexpected error: {error}"),
+            Ok(_) => panic!("decoded an unknown tag"),
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn span_traces() {
+        use std::collections::BTreeSet;
+
+        use codable::enc::ValueContainer as _;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        struct Negative;
+
+        impl Encode for Negative {
+            fn encode<'e, E>(&self, encoder: &mut E) -> codable::enc::EncodeResult<'e, E>
+            where
+                E: codable::enc::Encoder<'e>,
+            {
+                let con = encoder.as_value_container();
+                Err(con.custom_error("negative".to_string()))
+            }
+        }
+
+        let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+        tracing::subscriber::with_default(subscriber, || {
+            let value = to_value(&[vec![1u8], vec![2, 2]]).unwrap();
+            let error = tracing::info_span!("load_config")
+                .in_scope(|| from_value::<Vec<BTreeSet<u8>>>(&value))
+                .unwrap_err();
+            let trace = error.span_trace().expect("no span trace").to_string();
+            assert!(trace.contains("decode_seq"), "{trace}");
+            assert!(trace.contains("load_config"), "{trace}");
+
+            let error = tracing::info_span!("save_config")
+                .in_scope(|| to_value(&Negative))
+                .unwrap_err();
+            let trace = error.span_trace().expect("no span trace").to_string();
+            assert!(trace.contains("save_config"), "{trace}");
+
+            // Built by this crate rather than by `codable`.
+            let error = from_value::<u8>(&Value::Bool(true)).unwrap_err();
+            assert!(error.span_trace().is_none());
+        });
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn spans_and_events() {
+        use std::{
+            collections::{BTreeMap, BTreeSet},
+            sync::{Arc, Mutex},
+        };
+
+        use tracing::{field::Field, span, Event, Level, Subscriber};
+        use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+        #[derive(Clone, Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        struct Message(String);
+
+        impl tracing::field::Visit for Message {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{value:?}");
+                }
+            }
+        }
+
+        impl<S: Subscriber> Layer<S> for Recorder {
+            fn on_new_span(&self, attrs: &span::Attributes<'_>, _: &span::Id, _: Context<'_, S>) {
+                let name = attrs.metadata().name();
+                self.0.lock().unwrap().push(format!("span {name}"));
+            }
+
+            fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+                if *event.metadata().level() == Level::DEBUG {
+                    let mut message = Message(String::new());
+                    event.record(&mut message);
+                    self.0.lock().unwrap().push(format!("debug {}", message.0));
+                }
+            }
+        }
+
+        let recorder = Recorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let value = to_value(&BTreeMap::from([("a", vec![1u8, 1])])).unwrap();
+            from_value::<BTreeMap<String, BTreeSet<u8>>>(&value).unwrap_err();
+        });
+
+        let records = recorder.0.lock().unwrap();
+        let position = |record: &str| {
+            records
+                .iter()
+                .position(|x| x == record)
+                .unwrap_or_else(|| panic!("no `{record}` in {records:?}"))
+        };
+        let map = position("span decode_map");
+        let seq = position("span decode_seq");
+        let duplicate = position("debug duplicate set element");
+        assert!(map < seq && seq < duplicate, "{records:?}");
+    }
 }
@@ -3,9 +3,10 @@
 use darling::{export::NestedMeta, FromAttributes, FromDeriveInput, FromMeta};
 use heck::{ToKebabCase, ToLowerCamelCase, ToPascalCase, ToSnakeCase};
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{DataEnum, DataStruct, DeriveInput, Lit};
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, Copy)]
 enum RenameStyle {
     CamelCase,
@@ -45,25 +46,25 @@ impl FromMeta for TagPair {
 
         let name = match &items[0] {
             NestedMeta::Meta(_) => {
-                return Err(darling::Error::custom(format!(
-                    "Expected a string in first position of tag pair"
-                )));
+                return Err(darling::Error::custom(
+                    "Expected a string in first position of tag pair".to_string(),
+                ));
             }
             NestedMeta::Lit(x) => match x {
                 Lit::Str(x) => x.value(),
                 _ => {
-                    return Err(darling::Error::custom(format!(
-                        "Expected a string in first position of tag pair"
-                    )))
+                    return Err(darling::Error::custom(
+                        "Expected a string in first position of tag pair".to_string(),
+                    ))
                 }
             },
         };
 
         let value = match &items[1] {
             NestedMeta::Meta(_) => {
-                return Err(darling::Error::custom(format!(
-                    "Expected a literal in second position of tag pair"
-                )));
+                return Err(darling::Error::custom(
+                    "Expected a literal in second position of tag pair".to_string(),
+                ));
             }
             NestedMeta::Lit(x) => x.clone(),
         };
@@ -84,6 +85,9 @@ pub struct CodableAttrs {
 #[darling(attributes(codable))]
 pub struct CodableAttrAttrs {
     rename: Option<syn::LitStr>,
+    /// Decode a missing field as `Default::default()`.
+    #[darling(default)]
+    default: bool,
 }
 
 fn rename_input(style: RenameStyle, input: &str) -> String {
@@ -95,6 +99,18 @@ fn rename_input(style: RenameStyle, input: &str) -> String {
     }
 }
 
+fn variant_key(variant: &syn::Variant, attrs: &CodableAttrs) -> darling::Result<String> {
+    let local_attrs = CodableAttrAttrs::from_attributes(&variant.attrs)?;
+
+    Ok(if let Some(rename) = local_attrs.rename {
+        rename.value()
+    } else if let Some(rename) = attrs.rename {
+        rename_input(rename, &variant.ident.to_string())
+    } else {
+        variant.ident.to_string()
+    })
+}
+
 fn derive_encode_enum(
     data: DataEnum,
     attrs: CodableAttrs,
@@ -104,16 +120,7 @@ fn derive_encode_enum(
         .variants
         .iter()
         .map(|x| {
-            let local_attrs = CodableAttrAttrs::from_attributes(&x.attrs)?;
-
-            let key = if let Some(rename) = local_attrs.rename {
-                rename.value()
-            } else if let Some(rename) = attrs.rename {
-                rename_input(rename, &x.ident.to_string())
-            } else {
-                x.ident.to_string()
-            };
-
+            let key = variant_key(x, &attrs)?;
             let value = &x.ident;
 
             Ok::<TokenStream, darling::Error>(quote! {
@@ -202,7 +209,7 @@ fn derive_encode_struct(
 
             let is_option = if let syn::Type::Path(ty) = &field.ty {
                 let item = ty.path.segments.last().unwrap();
-                item.ident.to_string() == "Option"
+                item.ident == "Option"
             } else {
                 false
             };
@@ -259,14 +266,231 @@ pub fn derive_encode(item: TokenStream) -> Result<TokenStream, syn::Error> {
     match &input.data {
         syn::Data::Struct(x) => derive_encode_struct(x.clone(), attrs, input),
         syn::Data::Enum(x) => derive_encode_enum(x.clone(), attrs, input),
-        syn::Data::Union(_) => todo!(),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(input, "unions are not supported")),
     }
 }
 
 #[doc(hidden)]
-pub fn derive_decode(_item: TokenStream) -> Result<TokenStream, syn::Error> {
-    // Implement your proc-macro logic here. :)
+pub fn derive_to_coding_key(item: TokenStream) -> Result<TokenStream, syn::Error> {
+    let input: DeriveInput = syn::parse2(item)?;
+    let attrs = CodableAttrs::from_derive_input(&input)?;
+
+    let data = match &input.data {
+        syn::Data::Enum(x) => x,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "ToCodingKey can only be derived for enums",
+            ))
+        }
+    };
+
+    let variants = data
+        .variants
+        .iter()
+        .map(|x| {
+            if !matches!(x.fields, syn::Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    x,
+                    "ToCodingKey can only be derived for unit variants",
+                ));
+            }
+
+            let key = variant_key(x, &attrs)?;
+            let value = &x.ident;
+
+            Ok(quote! {
+                Self::#value => #key
+            })
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+
+    let enum_name = input.ident.clone();
+
     Ok(quote! {
-        "Hello world!"
+        impl ::codable::ToCodingKey for #enum_name {
+            fn to_coding_key(&self) -> ::codable::CodingKey<'_> {
+                ::codable::CodingKey::String(::codable::__private::Cow::Borrowed(match self {
+                    #(#variants),*
+                }))
+            }
+        }
     })
 }
+
+fn derive_decode_enum(
+    data: DataEnum,
+    attrs: CodableAttrs,
+    input: DeriveInput,
+) -> Result<TokenStream, syn::Error> {
+    let keys = data
+        .variants
+        .iter()
+        .map(|x| {
+            if !matches!(x.fields, syn::Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    x,
+                    "Decode can only be derived for unit variants",
+                ));
+            }
+            Ok(variant_key(x, &attrs)?)
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+    let idents = data.variants.iter().map(|x| &x.ident);
+
+    let enum_name = input.ident.clone();
+
+    // Tags are written alongside the variant by `Encode` but not checked here.
+    let (container, value, coding_path) = if attrs.tag.is_empty() {
+        (
+            quote! {
+                use ::codable::dec::ValueContainer as _;
+                let mut c = decoder.as_value_container()?;
+            },
+            quote! { c.decode_str()? },
+            quote! { c.coding_path().to_path_buf() },
+        )
+    } else {
+        (
+            quote! {
+                use ::codable::dec::KeyedContainer as _;
+                decoder.limits().check_depth(decoder.coding_path())?;
+                let mut c = decoder.as_container()?;
+            },
+            quote! { c.decode::<::codable::__private::Cow<'de, str>>(&"value")? },
+            quote! {
+                c.coding_path()
+                    .to_path_buf()
+                    .join(::codable::ToCodingKey::to_coding_key(&"value"))
+            },
+        )
+    };
+
+    Ok(quote! {
+        impl<'de> ::codable::dec::Decode<'de> for #enum_name {
+            fn decode<D>(decoder: &mut D) -> ::codable::dec::DecodeResult<'de, Self, D>
+            where
+                D: ::codable::dec::Decoder<'de>,
+            {
+                use ::codable::dec::CustomError as _;
+
+                const VARIANTS: &[&str] = &[#(#keys),*];
+
+                #container
+                let value = #value;
+                match &*value {
+                    #(#keys => Ok(Self::#idents),)*
+                    other => Err(D::Error::unknown_variant(#coding_path, other, VARIANTS)),
+                }
+            }
+        }
+    })
+}
+
+fn derive_decode_struct(
+    data: DataStruct,
+    attrs: CodableAttrs,
+    input: DeriveInput,
+) -> Result<TokenStream, syn::Error> {
+    let is_tuple_struct = data.fields.iter().any(|x| x.ident.is_none());
+    if is_tuple_struct {
+        return Err(syn::Error::new_spanned(
+            input,
+            "tuple structs are not supported",
+        ));
+    }
+
+    let mut decodes = vec![];
+    let mut required = vec![];
+    let mut names = vec![];
+    let mut locals = vec![];
+
+    for field in data.fields.iter() {
+        let value = field.ident.clone().unwrap();
+        let local = format_ident!("field_{}", value);
+        let local_attrs = CodableAttrAttrs::from_attributes(&field.attrs)?;
+        let key = if let Some(rename) = attrs.rename {
+            rename_input(rename, &value.to_string())
+        } else {
+            quote! { #value }.to_string()
+        };
+
+        let is_option = if let syn::Type::Path(ty) = &field.ty {
+            let item = ty.path.segments.last().unwrap();
+            item.ident == "Option"
+        } else {
+            false
+        };
+
+        // With errors being collected, a failed field is `None`, or its
+        // default if it has one.
+        decodes.push(if local_attrs.default {
+            quote! {
+                let #local = decoder
+                    .recover(|| {
+                        if c.contains(&#key) {
+                            c.decode(&#key)
+                        } else {
+                            Ok(::core::default::Default::default())
+                        }
+                    })?
+                    .unwrap_or_default();
+            }
+        } else if is_option {
+            quote! { let #local = decoder.recover(|| c.decode_option(&#key))?; }
+        } else {
+            quote! { let #local = decoder.recover(|| c.decode(&#key))?; }
+        });
+
+        if !local_attrs.default {
+            required.push(local.clone());
+        }
+        names.push(value);
+        locals.push(local);
+    }
+
+    let struct_name = input.ident.clone();
+    let construct = quote! {
+        Self {
+            #(#names: #locals),*
+        }
+    };
+    let body = if required.is_empty() {
+        quote! { Ok(#construct) }
+    } else {
+        quote! {
+            match (#(#required,)*) {
+                (#(Some(#required),)*) => Ok(#construct),
+                _ => Err(decoder.recovered_error()),
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl<'de> ::codable::dec::Decode<'de> for #struct_name {
+            fn decode<D>(decoder: &mut D) -> ::codable::dec::DecodeResult<'de, Self, D>
+            where
+                D: ::codable::dec::Decoder<'de>,
+            {
+                use ::codable::dec::KeyedContainer as _;
+
+                decoder.limits().check_depth(decoder.coding_path())?;
+                let mut c = decoder.as_container()?;
+                #(#decodes)*
+                #body
+            }
+        }
+    })
+}
+
+#[doc(hidden)]
+pub fn derive_decode(item: TokenStream) -> Result<TokenStream, syn::Error> {
+    let input: DeriveInput = syn::parse2(item)?;
+    let attrs = CodableAttrs::from_derive_input(&input)?;
+
+    match &input.data {
+        syn::Data::Struct(x) => derive_decode_struct(x.clone(), attrs, input),
+        syn::Data::Enum(x) => derive_decode_enum(x.clone(), attrs, input),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(input, "unions are not supported")),
+    }
+}
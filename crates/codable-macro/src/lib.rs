@@ -17,7 +17,9 @@ pub fn derive_encode(item: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_derive(Decode, attributes(codable))]
-/// Document your macro here.
+/// Derives `Decode` for a struct with named fields or an enum of unit variants,
+/// reading what `Encode` writes. Fields marked `#[codable(default)]` may be
+/// missing, and failed fields are recovered when the decoder collects errors.
 pub fn derive_decode(item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item as proc_macro2::TokenStream);
 
@@ -26,3 +28,15 @@ pub fn derive_decode(item: TokenStream) -> TokenStream {
         Err(err) => TokenStream::from(err.to_compile_error()),
     }
 }
+
+#[proc_macro_derive(ToCodingKey, attributes(codable))]
+/// Derives `ToCodingKey` for an enum of unit variants, using the same `rename`
+/// rules as `Encode`.
+pub fn derive_to_coding_key(item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as proc_macro2::TokenStream);
+
+    match codable_macro_impl::derive_to_coding_key(item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap as _, SerializeSeq as _},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use codable::{
+    dec::{self as dec, Decode, DecodeResult, Decoder, ValueKind},
+    enc::{self as enc, Encode, EncodeResult, Encoder},
+};
+
+use crate::error::Error;
+
+/// A value on its way between codable and serde.
+///
+/// Integers are kept at full width with their sign, so that nothing is lost
+/// whichever way they came in. Map keys are strings, as codable's keyed
+/// containers need them to be.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Content {
+    Null,
+    Bool(bool),
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<Content>),
+    Map(IndexMap<String, Content>),
+}
+
+impl Content {
+    pub(crate) fn kind(&self) -> ValueKind {
+        match self {
+            Content::Null => ValueKind::Null,
+            Content::Bool(_) => ValueKind::Bool,
+            Content::Signed(_) | Content::Unsigned(_) => ValueKind::Int,
+            Content::Float(_) => ValueKind::Float,
+            Content::String(_) => ValueKind::String,
+            Content::Bytes(_) => ValueKind::Bytes,
+            Content::Seq(_) => ValueKind::Seq,
+            Content::Map(_) => ValueKind::Map,
+        }
+    }
+
+    /// Turns a serde map key into a codable one. Like serde_json, integer and
+    /// bool keys are written out as strings.
+    pub(crate) fn into_key(self) -> Result<String, Error> {
+        match self {
+            Content::String(x) => Ok(x),
+            Content::Signed(x) => Ok(x.to_string()),
+            Content::Unsigned(x) => Ok(x.to_string()),
+            Content::Bool(x) => Ok(x.to_string()),
+            other => Err(de::Error::custom(format_args!(
+                "map keys must be strings, found {}",
+                other.kind().as_str()
+            ))),
+        }
+    }
+}
+
+macro_rules! from_prim {
+    ($variant:ident: $($ty:ty),*) => {
+        $(
+            impl From<$ty> for Content {
+                fn from(value: $ty) -> Self {
+                    Content::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+from_prim!(Unsigned: u8, u16, u32, u64, u128);
+from_prim!(Signed: i8, i16, i32, i64, i128);
+from_prim!(Float: f32, f64);
+from_prim!(Bool: bool);
+from_prim!(String: &str, String);
+
+impl From<usize> for Content {
+    fn from(value: usize) -> Self {
+        Content::Unsigned(value as u128)
+    }
+}
+
+impl From<isize> for Content {
+    fn from(value: isize) -> Self {
+        Content::Signed(value as i128)
+    }
+}
+
+impl Encode for Content {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        use enc::{KeyedContainer as _, SeqContainer as _, ValueContainer as _};
+
+        match self {
+            Content::Seq(values) => {
+                let mut con = encoder.as_seq_container();
+                for value in values {
+                    con.encode(value)?;
+                }
+                Ok(con.finish())
+            }
+            Content::Map(entries) => {
+                let mut con = encoder.as_container();
+                for (key, value) in entries {
+                    con.encode(value, key)?;
+                }
+                Ok(con.finish())
+            }
+            scalar => {
+                let mut con = encoder.as_value_container();
+                match scalar {
+                    Content::Null => con.encode_null()?,
+                    Content::Bool(x) => con.encode_bool(*x)?,
+                    Content::Signed(x) => match i64::try_from(*x) {
+                        Ok(x) => con.encode_i64(x)?,
+                        Err(_) => con.encode_i128(*x)?,
+                    },
+                    Content::Unsigned(x) => match u64::try_from(*x) {
+                        Ok(x) => con.encode_u64(x)?,
+                        Err(_) => con.encode_u128(*x)?,
+                    },
+                    Content::Float(x) => con.encode_f64(*x)?,
+                    Content::String(x) => con.encode_str(x)?,
+                    Content::Bytes(x) => con.encode_bytes(x)?,
+                    Content::Seq(_) | Content::Map(_) => unreachable!(),
+                }
+                Ok(con.finish())
+            }
+        }
+    }
+}
+
+impl<'de> Decode<'de> for Content {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        use dec::ValueContainer as _;
+
+        let content = match decoder.peek_kind()? {
+            ValueKind::Null => {
+                decoder.as_value_container()?.decode_null()?;
+                Content::Null
+            }
+            ValueKind::Bool => Content::Bool(decoder.as_value_container()?.decode_bool()?),
+            ValueKind::Int => {
+                let mut con = decoder.as_value_container()?;
+                // Integers beyond `i128::MAX` are still valid `u128`s.
+                match con.decode_i128() {
+                    Ok(x) => Content::Signed(x),
+                    Err(_) => Content::Unsigned(con.decode_u128()?),
+                }
+            }
+            ValueKind::Float => Content::Float(decoder.as_value_container()?.decode_f64()?),
+            ValueKind::String => Content::String(decoder.as_value_container()?.decode_string()?),
+            ValueKind::Bytes => {
+                Content::Bytes(decoder.as_value_container()?.decode_bytes()?.into_owned())
+            }
+            ValueKind::Seq => Content::Seq(Decode::decode(decoder)?),
+            ValueKind::Map => Content::Map(Decode::decode(decoder)?),
+        };
+        Ok(content)
+    }
+}
+
+impl Serialize for Content {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Content::Null => serializer.serialize_unit(),
+            Content::Bool(x) => serializer.serialize_bool(*x),
+            Content::Signed(x) => match i64::try_from(*x) {
+                Ok(x) => serializer.serialize_i64(x),
+                Err(_) => serializer.serialize_i128(*x),
+            },
+            Content::Unsigned(x) => match u64::try_from(*x) {
+                Ok(x) => serializer.serialize_u64(x),
+                Err(_) => serializer.serialize_u128(*x),
+            },
+            Content::Float(x) => serializer.serialize_f64(*x),
+            Content::String(x) => serializer.serialize_str(x),
+            Content::Bytes(x) => serializer.serialize_bytes(x),
+            Content::Seq(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Content::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ContentVisitor)
+    }
+}
+
+struct ContentVisitor;
+
+macro_rules! visit_prim {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method<E: de::Error>(self, value: $ty) -> Result<Content, E> {
+                Ok(Content::from(value))
+            }
+        )*
+    };
+}
+
+impl<'de> Visitor<'de> for ContentVisitor {
+    type Value = Content;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    visit_prim!(
+        visit_bool(bool),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f64(f64),
+        visit_str(&str),
+        visit_string(String)
+    );
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Content, E> {
+        Ok(Content::Bytes(value.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Content, E> {
+        Ok(Content::Bytes(value))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Content, E> {
+        Ok(Content::Null)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Content, E> {
+        Ok(Content::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Content, D::Error> {
+        Content::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Content, D::Error> {
+        Content::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Content, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Content::Seq(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Content, A::Error> {
+        let mut entries = IndexMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry::<Content, Content>()? {
+            let key = key.into_key().map_err(de::Error::custom)?;
+            entries.insert(key, value);
+        }
+        Ok(Content::Map(entries))
+    }
+}
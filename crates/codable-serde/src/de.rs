@@ -0,0 +1,259 @@
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+
+use crate::{content::Content, error::Error};
+
+/// Deserializes a serde value from [`Content`], for `AsCodable` to decode.
+pub(crate) struct ContentDeserializer {
+    content: Content,
+    human_readable: bool,
+}
+
+impl ContentDeserializer {
+    pub(crate) fn new(content: Content, human_readable: bool) -> Self {
+        Self {
+            content,
+            human_readable,
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ContentDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ContentDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let human_readable = self.human_readable;
+        let child = |content| ContentDeserializer::new(content, human_readable);
+        match self.content {
+            Content::Null => visitor.visit_unit(),
+            Content::Bool(x) => visitor.visit_bool(x),
+            Content::Signed(x) => match i64::try_from(x) {
+                Ok(x) => visitor.visit_i64(x),
+                Err(_) => visitor.visit_i128(x),
+            },
+            Content::Unsigned(x) => match u64::try_from(x) {
+                Ok(x) => visitor.visit_u64(x),
+                Err(_) => visitor.visit_u128(x),
+            },
+            Content::Float(x) => visitor.visit_f64(x),
+            Content::String(x) => visitor.visit_string(x),
+            Content::Bytes(x) => visitor.visit_byte_buf(x),
+            Content::Seq(values) => {
+                let mut seq = SeqDeserializer::new(values.into_iter().map(child));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Content::Map(entries) => {
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| (KeyDeserializer(key), child(value)));
+                let mut map = MapDeserializer::new(entries);
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.content {
+            Content::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let human_readable = self.human_readable;
+        let (variant, value) = match self.content {
+            Content::String(variant) => (variant, None),
+            Content::Map(entries) if entries.len() == 1 => {
+                let (variant, value) = entries.into_iter().next().unwrap();
+                (
+                    variant,
+                    Some(ContentDeserializer::new(value, human_readable)),
+                )
+            }
+            other => {
+                return Err(de::Error::custom(format_args!(
+                    "invalid type: expected a variant name or a map with a single key, found {}",
+                    other.kind().as_str()
+                )))
+            }
+        };
+        visitor.visit_enum(EnumDeserializer { variant, value })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Deserializes a map key. Keys are strings on the codable side, so integer
+/// and bool keys are parsed back out of them, as serde_json does.
+struct KeyDeserializer(String);
+
+impl<'de> IntoDeserializer<'de, Error> for KeyDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse() {
+                    Ok(x) => visitor.$visit(x),
+                    Err(_) => visitor.visit_string(self.0),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    deserialize_parsed_key!(
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128
+    );
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(EnumDeserializer {
+            variant: self.0,
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        f32 f64 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    /// The variant's contents, absent for a unit variant written as its name.
+    value: Option<ContentDeserializer>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantDeserializer), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<ContentDeserializer>,
+}
+
+impl VariantDeserializer {
+    fn value(self, expected: &str) -> Result<ContentDeserializer, Error> {
+        self.value.ok_or_else(|| {
+            de::Error::custom(format_args!(
+                "invalid type: expected {expected}, found a unit variant"
+            ))
+        })
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.value {
+            Some(value) => de::Deserialize::deserialize(value),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self.value("a newtype variant")?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self.value("a tuple variant")?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self.value("a struct variant")?, visitor)
+    }
+}
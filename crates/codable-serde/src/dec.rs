@@ -0,0 +1,419 @@
+use std::borrow::Cow;
+
+use indexmap::IndexMap;
+
+use codable::{
+    dec::{self, CustomError, Decode, Decoder, ValueContainer as _, ValueKind},
+    CodingKey, CodingPath, ToCodingKey,
+};
+
+use crate::{content::Content, error::Error};
+
+/// Decodes a codable value from [`Content`], for `AsSerde` to deserialize.
+pub(crate) struct ContentDecoder<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+    value: &'a Content,
+}
+
+impl<'a> ContentDecoder<'a> {
+    pub(crate) fn new(value: &'a Content, human_readable: bool) -> Self {
+        Self {
+            coding_path: CodingPath::root(),
+            human_readable,
+            value,
+        }
+    }
+}
+
+pub(crate) struct KeyedContainer<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+    value: &'a IndexMap<String, Content>,
+}
+
+pub(crate) struct ValueContainer<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+    value: &'a Content,
+}
+
+pub(crate) struct SeqContainer<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+    value: &'a [Content],
+    cursor_index: usize,
+}
+
+fn invalid_type(coding_path: &CodingPath<'_>, expected: &str, found: &Content) -> Error {
+    Error::invalid_type(coding_path.to_path_buf(), expected, found.kind().as_str())
+}
+
+fn int<T>(coding_path: &CodingPath<'_>, value: &Content) -> Result<T, Error>
+where
+    T: TryFrom<i128> + TryFrom<u128>,
+{
+    let (converted, text) = match value {
+        Content::Signed(x) => (T::try_from(*x).ok(), x.to_string()),
+        Content::Unsigned(x) => (T::try_from(*x).ok(), x.to_string()),
+        other => return Err(invalid_type(coding_path, "integer", other)),
+    };
+    converted.ok_or_else(|| {
+        Error::out_of_range(coding_path.to_path_buf(), &text, std::any::type_name::<T>())
+    })
+}
+
+fn float(coding_path: &CodingPath<'_>, value: &Content) -> Result<f64, Error> {
+    match value {
+        Content::Float(x) => Ok(*x),
+        Content::Signed(x) => Ok(*x as f64),
+        Content::Unsigned(x) => Ok(*x as f64),
+        other => Err(invalid_type(coding_path, "float", other)),
+    }
+}
+
+impl<'a> KeyedContainer<'a> {
+    fn get(&self, key: &impl ToCodingKey) -> Result<ValueContainer<'a>, Error> {
+        let key = key.to_coding_key().into_owned();
+        match self.value.get(&*key.as_str()) {
+            Some(value) => Ok(ValueContainer {
+                coding_path: self.coding_path.join_shared(key),
+                human_readable: self.human_readable,
+                value,
+            }),
+            None => Err(Error::missing_key(
+                self.coding_path.to_path_buf(),
+                &key.as_str(),
+            )),
+        }
+    }
+}
+
+impl<'a> SeqContainer<'a> {
+    fn current(&self) -> Result<ValueContainer<'a>, Error> {
+        match self.value.get(self.cursor_index) {
+            Some(value) => Ok(ValueContainer {
+                coding_path: self.coding_path.clone(),
+                human_readable: self.human_readable,
+                value,
+            }),
+            None => Err(Error::custom(
+                self.coding_path.to_path_buf(),
+                format!("the sequence has only {} elements", self.value.len()),
+            )),
+        }
+    }
+
+    fn increment_cursor(&mut self) {
+        self.cursor_index += 1;
+        self.coding_path.replace(CodingKey::Int(self.cursor_index));
+    }
+}
+
+macro_rules! decode_keyed {
+    ($($method:ident -> $ty:ty),*) => {
+        $(
+            fn $method(&mut self, key: &impl ToCodingKey) -> Result<$ty, Error> {
+                self.get(key)?.$method()
+            }
+        )*
+    };
+}
+
+macro_rules! decode_int {
+    ($($method:ident -> $ty:ty),*) => {
+        $(
+            fn $method(&mut self) -> Result<$ty, Error> {
+                int(&self.coding_path, self.value)
+            }
+        )*
+    };
+}
+
+macro_rules! decode_seq {
+    ($($method:ident -> $ty:ty),*) => {
+        $(
+            fn $method(&mut self) -> Result<$ty, Error> {
+                let result = self.current()?.$method();
+                self.increment_cursor();
+                result
+            }
+        )*
+    };
+}
+
+impl<'c> dec::KeyedContainer<'c> for KeyedContainer<'c> {
+    type Error = Error;
+    type Value = Content;
+    type Keys<'a> = indexmap::map::Keys<'a, String, Content> where Self: 'a;
+    type Decoder = ContentDecoder<'c>;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    fn contains(&self, coding_key: &impl ToCodingKey) -> bool {
+        self.value.contains_key(&*coding_key.as_str())
+    }
+
+    fn keys<'a>(&'a self) -> Self::Keys<'a> {
+        self.value.keys()
+    }
+
+    decode_keyed!(
+        decode_u8 -> u8,
+        decode_u16 -> u16,
+        decode_u32 -> u32,
+        decode_u64 -> u64,
+        decode_u128 -> u128,
+        decode_usize -> usize,
+        decode_i8 -> i8,
+        decode_i16 -> i16,
+        decode_i32 -> i32,
+        decode_i64 -> i64,
+        decode_i128 -> i128,
+        decode_isize -> isize,
+        decode_string -> String,
+        decode_str -> Cow<'c, str>,
+        decode_bytes -> Cow<'c, [u8]>,
+        decode_f32 -> f32,
+        decode_f64 -> f64,
+        decode_bool -> bool
+    );
+
+    fn decode_option<T: Decode<'c>>(&mut self, key: &impl ToCodingKey) -> Result<Option<T>, Error> {
+        match self.value.get(&*key.as_str()) {
+            Some(_) => self.get(key)?.decode_option(),
+            None => Ok(None),
+        }
+    }
+
+    fn decode<T: Decode<'c>>(&mut self, key: &impl ToCodingKey) -> Result<T, Error> {
+        self.get(key)?.decode()
+    }
+
+    fn nested_container(
+        &mut self,
+        key: &impl ToCodingKey,
+    ) -> Result<<Self::Decoder as Decoder<'c>>::KeyedContainer, Error> {
+        self.get(key)?.decoder().as_container()
+    }
+
+    fn nested_seq_container(
+        &mut self,
+        key: &impl ToCodingKey,
+    ) -> Result<<Self::Decoder as Decoder<'c>>::SeqContainer, Error> {
+        self.get(key)?.decoder().as_seq_container()
+    }
+}
+
+impl<'a> ValueContainer<'a> {
+    fn decoder(&self) -> ContentDecoder<'a> {
+        ContentDecoder {
+            coding_path: self.coding_path.clone(),
+            human_readable: self.human_readable,
+            value: self.value,
+        }
+    }
+}
+
+impl<'c> dec::ValueContainer<'c> for ValueContainer<'c> {
+    type Error = Error;
+    type Value = Content;
+    type Decoder = ContentDecoder<'c>;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    decode_int!(
+        decode_u8 -> u8,
+        decode_u16 -> u16,
+        decode_u32 -> u32,
+        decode_u64 -> u64,
+        decode_u128 -> u128,
+        decode_usize -> usize,
+        decode_i8 -> i8,
+        decode_i16 -> i16,
+        decode_i32 -> i32,
+        decode_i64 -> i64,
+        decode_i128 -> i128,
+        decode_isize -> isize
+    );
+
+    fn decode_string(&mut self) -> Result<String, Error> {
+        self.decode_str().map(Cow::into_owned)
+    }
+
+    fn decode_str(&mut self) -> Result<Cow<'c, str>, Error> {
+        match self.value {
+            Content::String(x) => Ok(Cow::Borrowed(x)),
+            other => Err(invalid_type(&self.coding_path, "string", other)),
+        }
+    }
+
+    fn decode_bytes(&mut self) -> Result<Cow<'c, [u8]>, Error> {
+        match self.value {
+            Content::Bytes(x) => Ok(Cow::Borrowed(x)),
+            // Formats without byte strings hand them over as sequences.
+            Content::Seq(_) => self.decode::<Vec<u8>>().map(Cow::Owned),
+            other => Err(invalid_type(&self.coding_path, "bytes", other)),
+        }
+    }
+
+    fn decode_f32(&mut self) -> Result<f32, Error> {
+        float(&self.coding_path, self.value).map(|x| x as f32)
+    }
+
+    fn decode_f64(&mut self) -> Result<f64, Error> {
+        float(&self.coding_path, self.value)
+    }
+
+    fn decode_bool(&mut self) -> Result<bool, Error> {
+        match self.value {
+            Content::Bool(x) => Ok(*x),
+            other => Err(invalid_type(&self.coding_path, "bool", other)),
+        }
+    }
+
+    fn decode_null(&mut self) -> Result<(), Error> {
+        match self.value {
+            Content::Null => Ok(()),
+            other => Err(invalid_type(&self.coding_path, "null", other)),
+        }
+    }
+
+    fn decode_option<T: Decode<'c>>(&mut self) -> Result<Option<T>, Error> {
+        match self.value {
+            Content::Null => Ok(None),
+            _ => self.decode().map(Some),
+        }
+    }
+
+    fn decode<T: Decode<'c>>(&mut self) -> Result<T, Error> {
+        T::decode(&mut self.decoder())
+    }
+}
+
+impl<'c> dec::SeqContainer<'c> for SeqContainer<'c> {
+    type Error = Error;
+    type Value = Content;
+    type Decoder = ContentDecoder<'c>;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    fn cursor_index(&self) -> usize {
+        self.cursor_index
+    }
+
+    fn is_at_end(&mut self) -> Result<bool, Error> {
+        Ok(self.cursor_index >= self.value.len())
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.value.len())
+    }
+
+    decode_seq!(
+        decode_u8 -> u8,
+        decode_u16 -> u16,
+        decode_u32 -> u32,
+        decode_u64 -> u64,
+        decode_u128 -> u128,
+        decode_usize -> usize,
+        decode_i8 -> i8,
+        decode_i16 -> i16,
+        decode_i32 -> i32,
+        decode_i64 -> i64,
+        decode_i128 -> i128,
+        decode_isize -> isize,
+        decode_string -> String,
+        decode_str -> Cow<'c, str>,
+        decode_bytes -> Cow<'c, [u8]>,
+        decode_f32 -> f32,
+        decode_f64 -> f64,
+        decode_bool -> bool
+    );
+
+    fn decode_option<T: Decode<'c>>(&mut self) -> Result<Option<T>, Error> {
+        let result = self.current()?.decode_option();
+        self.increment_cursor();
+        result
+    }
+
+    fn decode<T: Decode<'c>>(&mut self) -> Result<T, Error> {
+        let result = self.current()?.decode();
+        self.increment_cursor();
+        result
+    }
+
+    fn nested_container(
+        &mut self,
+    ) -> Result<<Self::Decoder as Decoder<'c>>::KeyedContainer, Error> {
+        let result = self.current()?.decoder().as_container();
+        self.increment_cursor();
+        result
+    }
+
+    fn nested_seq_container(
+        &mut self,
+    ) -> Result<<Self::Decoder as Decoder<'c>>::SeqContainer, Error> {
+        let result = self.current()?.decoder().as_seq_container();
+        self.increment_cursor();
+        result
+    }
+}
+
+impl<'r> Decoder<'r> for ContentDecoder<'r> {
+    type Value = Content;
+    type Error = Error;
+
+    type KeyedContainer = KeyedContainer<'r>;
+    type ValueContainer = ValueContainer<'r>;
+    type SeqContainer = SeqContainer<'r>;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+
+    fn peek_kind(&mut self) -> Result<ValueKind, Error> {
+        Ok(self.value.kind())
+    }
+
+    fn as_container(&mut self) -> Result<Self::KeyedContainer, Error> {
+        match self.value {
+            Content::Map(value) => Ok(KeyedContainer {
+                coding_path: self.coding_path.clone(),
+                human_readable: self.human_readable,
+                value,
+            }),
+            other => Err(invalid_type(&self.coding_path, "map", other)),
+        }
+    }
+
+    fn as_value_container(&mut self) -> Result<Self::ValueContainer, Error> {
+        Ok(ValueContainer {
+            coding_path: self.coding_path.clone(),
+            human_readable: self.human_readable,
+            value: self.value,
+        })
+    }
+
+    fn as_seq_container(&mut self) -> Result<Self::SeqContainer, Error> {
+        match self.value {
+            Content::Seq(value) => Ok(SeqContainer {
+                coding_path: self.coding_path.join_shared(CodingKey::Int(0)),
+                human_readable: self.human_readable,
+                value,
+                cursor_index: 0,
+            }),
+            other => Err(invalid_type(&self.coding_path, "sequence", other)),
+        }
+    }
+}
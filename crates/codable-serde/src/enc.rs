@@ -0,0 +1,348 @@
+use std::borrow::Cow;
+
+use indexmap::IndexMap;
+
+use codable::{
+    enc::{self, Encode, Encoder},
+    CodingKey, CodingPath, ToCodingKey,
+};
+
+use crate::{content::Content, error::Error};
+
+/// Encodes a codable value into [`Content`], for `AsSerde` to serialize.
+pub(crate) struct ContentEncoder<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+}
+
+impl<'a> ContentEncoder<'a> {
+    pub(crate) fn new(human_readable: bool) -> Self {
+        Self::with_path(CodingPath::root(), human_readable)
+    }
+
+    fn with_path(coding_path: CodingPath<'a>, human_readable: bool) -> Self {
+        Self {
+            coding_path,
+            human_readable,
+        }
+    }
+}
+
+impl<'r> Encoder<'r> for ContentEncoder<'r> {
+    type Value = Content;
+    type Error = Error;
+
+    type KeyedContainer = KeyedContainer<'r> where Self: 'r;
+    type ValueContainer = ValueContainer<'r> where Self: 'r;
+    type SeqContainer = SeqContainer<'r> where Self: 'r;
+
+    fn as_container(&mut self) -> Self::KeyedContainer {
+        KeyedContainer {
+            coding_path: self.coding_path.clone(),
+            human_readable: self.human_readable,
+            value: IndexMap::new(),
+        }
+    }
+
+    fn as_value_container(&mut self) -> Self::ValueContainer {
+        ValueContainer {
+            coding_path: self.coding_path.clone(),
+            human_readable: self.human_readable,
+            value: None,
+        }
+    }
+
+    fn as_seq_container(&mut self) -> Self::SeqContainer {
+        SeqContainer {
+            coding_path: self.coding_path.join_shared(CodingKey::Int(0)),
+            human_readable: self.human_readable,
+            values: vec![],
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+}
+
+pub(crate) struct KeyedContainer<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+    value: IndexMap<String, Content>,
+}
+
+pub(crate) struct ValueContainer<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+    value: Option<Content>,
+}
+
+pub(crate) struct SeqContainer<'a> {
+    coding_path: CodingPath<'a>,
+    human_readable: bool,
+    values: Vec<Content>,
+}
+
+impl KeyedContainer<'_> {
+    fn insert(&mut self, key: &impl ToCodingKey, value: Content) {
+        self.value.insert(key.as_str().into_owned(), value);
+    }
+}
+
+impl SeqContainer<'_> {
+    fn push(&mut self, value: Content) {
+        self.values.push(value);
+        self.coding_path.replace(CodingKey::Int(self.values.len()));
+    }
+}
+
+/// Containers here are buffers, so a nested container would have nowhere to
+/// write to; values are nested with `encode` instead.
+fn nested_unsupported(coding_path: &CodingPath<'_>) -> Error {
+    enc::CustomError::custom(
+        coding_path.to_path_buf(),
+        "nested containers are not supported when serializing through serde".to_string(),
+    )
+}
+
+macro_rules! encode_keyed {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method(&mut self, value: $ty, key: &impl ToCodingKey) -> Result<(), Error> {
+                self.insert(key, Content::from(value));
+                Ok(())
+            }
+        )*
+    };
+}
+
+macro_rules! encode_value {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method(&mut self, value: $ty) -> Result<(), Error> {
+                self.value = Some(Content::from(value));
+                Ok(())
+            }
+        )*
+    };
+}
+
+macro_rules! encode_seq {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method(&mut self, value: $ty) -> Result<(), Error> {
+                self.push(Content::from(value));
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl<'c> enc::KeyedContainer for KeyedContainer<'c> {
+    type Error = Error;
+    type Value = Content;
+    type Encoder<'a> = ContentEncoder<'a> where Self: 'a;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    encode_keyed!(
+        encode_u8(u8),
+        encode_u16(u16),
+        encode_u32(u32),
+        encode_u64(u64),
+        encode_u128(u128),
+        encode_usize(usize),
+        encode_i8(i8),
+        encode_i16(i16),
+        encode_i32(i32),
+        encode_i64(i64),
+        encode_i128(i128),
+        encode_isize(isize),
+        encode_f32(f32),
+        encode_f64(f64),
+        encode_bool(bool)
+    );
+
+    fn encode_str<'s, S: Into<Cow<'s, str>>>(
+        &mut self,
+        value: S,
+        key: &impl ToCodingKey,
+    ) -> Result<(), Error> {
+        self.insert(key, Content::String(value.into().into_owned()));
+        Ok(())
+    }
+
+    fn encode_bytes(&mut self, value: &[u8], key: &impl ToCodingKey) -> Result<(), Error> {
+        self.insert(key, Content::Bytes(value.to_vec()));
+        Ok(())
+    }
+
+    fn encode_option<T: Encode>(
+        &mut self,
+        value: Option<&T>,
+        key: &impl ToCodingKey,
+    ) -> Result<(), Error> {
+        match value {
+            Some(x) => self.encode(x, key),
+            None => {
+                self.insert(key, Content::Null);
+                Ok(())
+            }
+        }
+    }
+
+    fn encode<T: Encode>(&mut self, value: &T, key: &impl ToCodingKey) -> Result<(), Error> {
+        let coding_path = self.coding_path.join(key.to_coding_key().into_owned());
+        let value = value.encode(&mut ContentEncoder::with_path(
+            coding_path,
+            self.human_readable,
+        ))?;
+        self.insert(key, value);
+        Ok(())
+    }
+
+    fn nested_container<'a>(
+        &'a mut self,
+        _key: &'a impl ToCodingKey,
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::KeyedContainer, Error> {
+        Err(nested_unsupported(&self.coding_path))
+    }
+
+    fn nested_seq_container<'a>(
+        &'a mut self,
+        _key: &'a impl ToCodingKey,
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::SeqContainer, Error> {
+        Err(nested_unsupported(&self.coding_path))
+    }
+
+    fn finish(self) -> Content {
+        Content::Map(self.value)
+    }
+}
+
+impl<'c> enc::ValueContainer for ValueContainer<'c> {
+    type Error = Error;
+    type Value = Content;
+    type Encoder<'a> = ContentEncoder<'a>;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    encode_value!(
+        encode_u8(u8),
+        encode_u16(u16),
+        encode_u32(u32),
+        encode_u64(u64),
+        encode_u128(u128),
+        encode_usize(usize),
+        encode_i8(i8),
+        encode_i16(i16),
+        encode_i32(i32),
+        encode_i64(i64),
+        encode_i128(i128),
+        encode_isize(isize),
+        encode_str(&str),
+        encode_f32(f32),
+        encode_f64(f64),
+        encode_bool(bool)
+    );
+
+    fn encode_bytes(&mut self, value: &[u8]) -> Result<(), Error> {
+        self.value = Some(Content::Bytes(value.to_vec()));
+        Ok(())
+    }
+
+    fn encode_null(&mut self) -> Result<(), Error> {
+        self.value = Some(Content::Null);
+        Ok(())
+    }
+
+    fn encode_option<T: Encode>(&mut self, value: Option<&T>) -> Result<(), Error> {
+        match value {
+            Some(x) => self.encode(x),
+            None => self.encode_null(),
+        }
+    }
+
+    fn encode<T: Encode>(&mut self, value: &T) -> Result<(), Error> {
+        let mut encoder = ContentEncoder::with_path(self.coding_path.clone(), self.human_readable);
+        self.value = Some(value.encode(&mut encoder)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Content {
+        self.value.unwrap_or(Content::Null)
+    }
+}
+
+impl<'c> enc::SeqContainer for SeqContainer<'c> {
+    type Error = Error;
+    type Value = Content;
+    type Encoder<'a> = ContentEncoder<'a> where Self: 'a;
+
+    fn coding_path(&self) -> &CodingPath<'_> {
+        &self.coding_path
+    }
+
+    encode_seq!(
+        encode_u8(u8),
+        encode_u16(u16),
+        encode_u32(u32),
+        encode_u64(u64),
+        encode_u128(u128),
+        encode_usize(usize),
+        encode_i8(i8),
+        encode_i16(i16),
+        encode_i32(i32),
+        encode_i64(i64),
+        encode_i128(i128),
+        encode_isize(isize),
+        encode_str(&str),
+        encode_f32(f32),
+        encode_f64(f64),
+        encode_bool(bool)
+    );
+
+    fn encode_bytes(&mut self, value: &[u8]) -> Result<(), Error> {
+        self.push(Content::Bytes(value.to_vec()));
+        Ok(())
+    }
+
+    fn encode_null(&mut self) -> Result<(), Error> {
+        self.push(Content::Null);
+        Ok(())
+    }
+
+    fn encode_option<T: Encode>(&mut self, value: Option<&T>) -> Result<(), Error> {
+        match value {
+            Some(x) => self.encode(x),
+            None => self.encode_null(),
+        }
+    }
+
+    fn encode<T: Encode>(&mut self, value: &T) -> Result<(), Error> {
+        let mut encoder = ContentEncoder::with_path(self.coding_path.clone(), self.human_readable);
+        let value = value.encode(&mut encoder)?;
+        self.push(value);
+        Ok(())
+    }
+
+    fn nested_container<'a>(
+        &'a mut self,
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::KeyedContainer, Error> {
+        Err(nested_unsupported(&self.coding_path))
+    }
+
+    fn nested_seq_container<'a>(
+        &'a mut self,
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::SeqContainer, Error> {
+        Err(nested_unsupported(&self.coding_path))
+    }
+
+    fn finish(self) -> Content {
+        Content::Seq(self.values)
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::{self, Display};
+
+use codable::{dec, enc, CodingPathBuf};
+
+/// The error of every encoder, decoder, serializer and deserializer in this
+/// crate. It never reaches the caller as is: each adapter turns it into the
+/// error type of the format on the other side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Error {
+    coding_path: CodingPathBuf,
+    message: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.coding_path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.coding_path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl enc::CustomError for Error {
+    fn custom(coding_path: CodingPathBuf, message: String) -> Self {
+        Self {
+            coding_path,
+            message,
+        }
+    }
+}
+
+impl dec::CustomError for Error {
+    fn custom(coding_path: CodingPathBuf, message: String) -> Self {
+        Self {
+            coding_path,
+            message,
+        }
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: Display>(message: T) -> Self {
+        Self {
+            coding_path: CodingPathBuf::root(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(message: T) -> Self {
+        Self {
+            coding_path: CodingPathBuf::root(),
+            message: message.to_string(),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Adapters between codable and serde.
+//!
+//! [`AsSerde`] lets a codable type be serialized and deserialized with any
+//! serde format, and [`AsCodable`] lets a serde type sit inside a codable
+//! struct. Inside a serde struct, a codable field can also be bridged with
+//! `#[serde(with = "codable_serde")]`, which uses [`serialize`] and
+//! [`deserialize`]:
+//!
+//! ```ignore
+//! let json = serde_json::to_string(&AsSerde(config))?;
+//!
+//! #[derive(Encode, Decode)]
+//! struct Event {
+//!     // `Payload` only implements `Serialize` and `Deserialize`.
+//!     payload: AsCodable<Payload>,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Request {
+//!     #[serde(with = "codable_serde")]
+//!     event: Event,
+//! }
+//! ```
+//!
+//! Codable containers may be read in any order and serde's data model is a
+//! stream, so each value passes through an owned intermediate tree on its way
+//! across. Decoding a codable type needs a self-describing serde format, and
+//! decoding a serde type needs a self-describing codable format (one that
+//! implements `peek_kind`), since the tree is built before the type gets to
+//! say what it expects.
+//!
+//! Sequences and maps keep their order. Serde enums use serde_json's
+//! externally tagged layout: a unit variant is its name, and any other variant
+//! is a map from its name to its contents. `is_human_readable` is passed
+//! across in both directions.
+
+#![deny(rust_2018_idioms)]
+
+mod content;
+mod de;
+mod dec;
+mod enc;
+mod error;
+mod ser;
+
+use codable::{
+    dec::{CustomError as _, Decode, DecodeOwned, DecodeResult, Decoder},
+    enc::{Encode, EncodeResult, Encoder, ValueContainer as _},
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{content::Content, de::ContentDeserializer, dec::ContentDecoder, enc::ContentEncoder};
+
+/// Implements `Serialize` and `Deserialize` for a codable type.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AsSerde<T>(pub T);
+
+/// Implements `Encode` and `Decode` for a serde type.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AsCodable<T>(pub T);
+
+impl<T> AsSerde<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> AsCodable<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Serializes a codable value, for `#[serde(serialize_with)]`.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Encode + ?Sized,
+    S: Serializer,
+{
+    let mut encoder = ContentEncoder::new(serializer.is_human_readable());
+    let content = value
+        .encode(&mut encoder)
+        .map_err(serde::ser::Error::custom)?;
+    content.serialize(serializer)
+}
+
+/// Deserializes a codable value, for `#[serde(deserialize_with)]`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: DecodeOwned,
+    D: Deserializer<'de>,
+{
+    let human_readable = deserializer.is_human_readable();
+    let content = Content::deserialize(deserializer)?;
+    let mut decoder = ContentDecoder::new(&content, human_readable);
+    T::decode(&mut decoder).map_err(serde::de::Error::custom)
+}
+
+impl<T: Encode> Serialize for AsSerde<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: DecodeOwned> Deserialize<'de> for AsSerde<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(AsSerde)
+    }
+}
+
+impl<T: Serialize> Encode for AsCodable<T> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        match self
+            .0
+            .serialize(ser::ContentSerializer::new(encoder.is_human_readable()))
+        {
+            Ok(content) => content.encode(encoder),
+            Err(error) => Err(encoder.as_value_container().custom_error(error.to_string())),
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Decode<'de> for AsCodable<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let content = Content::decode(decoder)?;
+        let deserializer = ContentDeserializer::new(content, decoder.is_human_readable());
+        T::deserialize(deserializer)
+            .map(AsCodable)
+            .map_err(|error| {
+                D::Error::custom(decoder.coding_path().to_path_buf(), error.to_string())
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use codable::{
+        dec::KeyedContainer as _,
+        enc::{KeyedContainer as _, ValueContainer as _},
+    };
+    use codable_json::{from_value, to_value, to_value_with, JsonOptions, Value};
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+        label: Option<String>,
+    }
+
+    impl Encode for Point {
+        fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+        where
+            E: Encoder<'e>,
+        {
+            let mut con = encoder.as_container();
+            con.encode_i32(self.x, &"x")?;
+            con.encode_i32(self.y, &"y")?;
+            con.encode_option(self.label.as_ref(), &"label")?;
+            Ok(con.finish())
+        }
+    }
+
+    impl<'de> Decode<'de> for Point {
+        fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+        where
+            D: Decoder<'de>,
+        {
+            let mut con = decoder.as_container()?;
+            Ok(Point {
+                x: con.decode_i32(&"x")?,
+                y: con.decode_i32(&"y")?,
+                label: con.decode_option(&"label")?,
+            })
+        }
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Line(Point2, Point2),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Point2(i64, i64);
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Drawing {
+        name: String,
+        layer: Option<u8>,
+        shapes: Vec<Shape>,
+        ids: BTreeMap<u32, String>,
+    }
+
+    #[test]
+    fn codable_type_through_serde() {
+        let point = AsSerde(Point {
+            x: 1,
+            y: -2,
+            label: None,
+        });
+        let json = serde_json::to_value(&point).unwrap();
+        assert_eq!(json, json!({ "x": 1, "y": -2, "label": null }));
+
+        let decoded = serde_json::from_value::<AsSerde<Point>>(json).unwrap();
+        assert_eq!(decoded, point);
+
+        let error = serde_json::from_str::<AsSerde<Point>>(r#"{"x": 1}"#).unwrap_err();
+        assert_eq!(error.to_string(), "missing key `y`");
+    }
+
+    #[test]
+    fn serde_type_through_codable() {
+        let drawing = Drawing {
+            name: "plan".to_string(),
+            layer: None,
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Line(Point2(0, 0), Point2(-3, 4)),
+                Shape::Rect {
+                    width: 2,
+                    height: 3,
+                },
+            ],
+            ids: BTreeMap::from([(7, "seven".to_string())]),
+        };
+        let value = to_value(&AsCodable(&drawing)).unwrap();
+        // The same layout serde_json gives it.
+        assert_eq!(value, Value::from(serde_json::to_value(&drawing).unwrap()));
+
+        let AsCodable(decoded) = from_value::<AsCodable<Drawing>>(&value).unwrap();
+        assert_eq!(decoded, drawing);
+
+        let value = Value::from(json!({ "name": "plan", "shapes": [{ "Square": 1 }], "ids": {} }));
+        let error = from_value::<AsCodable<Drawing>>(&value).unwrap_err();
+        assert!(
+            error.to_string().contains("unknown variant `Square`"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn nested_both_ways() {
+        // A serde type inside a codable type inside a serde format.
+        struct Wrapper {
+            shape: AsCodable<Shape>,
+        }
+
+        impl Encode for Wrapper {
+            fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+            where
+                E: Encoder<'e>,
+            {
+                let mut con = encoder.as_container();
+                con.encode(&self.shape, &"shape")?;
+                Ok(con.finish())
+            }
+        }
+
+        #[derive(serde::Serialize)]
+        struct Request {
+            #[serde(serialize_with = "crate::serialize")]
+            wrapper: Wrapper,
+        }
+
+        let request = Request {
+            wrapper: Wrapper {
+                shape: AsCodable(Shape::Rect {
+                    width: 1,
+                    height: 2,
+                }),
+            },
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"wrapper":{"shape":{"Rect":{"width":1,"height":2}}}}"#
+        );
+    }
+
+    #[test]
+    fn human_readable() {
+        struct SerdeProbe;
+
+        impl Serialize for SerdeProbe {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let human_readable = serializer.is_human_readable();
+                serializer.serialize_bool(human_readable)
+            }
+        }
+
+        struct CodableProbe;
+
+        impl Encode for CodableProbe {
+            fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+            where
+                E: Encoder<'e>,
+            {
+                let human_readable = encoder.is_human_readable();
+                let mut con = encoder.as_value_container();
+                con.encode_bool(human_readable)?;
+                Ok(con.finish())
+            }
+        }
+
+        let options = JsonOptions {
+            compact: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            to_value_with(&AsCodable(SerdeProbe), options).unwrap(),
+            Value::Bool(false)
+        );
+        assert_eq!(to_value(&AsCodable(SerdeProbe)).unwrap(), Value::Bool(true));
+        assert_eq!(
+            serde_json::to_value(AsSerde(CodableProbe)).unwrap(),
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn error_paths() {
+        let error = serde_json::from_value::<AsSerde<Vec<Point>>>(json!([
+            { "x": 1, "y": 2 },
+            { "x": 1, "y": "two" },
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "[1].y: invalid type: expected integer, found string"
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use indexmap::IndexMap;
+use serde::{ser, Serialize};
+
+use crate::{content::Content, error::Error};
+
+/// Serializes a serde value into [`Content`], for `AsCodable` to encode.
+#[derive(Clone, Copy)]
+pub(crate) struct ContentSerializer {
+    human_readable: bool,
+}
+
+impl ContentSerializer {
+    pub(crate) fn new(human_readable: bool) -> Self {
+        Self { human_readable }
+    }
+
+    fn content<T: Serialize + ?Sized>(self, value: &T) -> Result<Content, Error> {
+        value.serialize(self)
+    }
+
+    /// Wraps a variant's contents in a map keyed by its name.
+    fn variant(variant: &str, content: Content) -> Content {
+        Content::Map(IndexMap::from([(variant.to_string(), content)]))
+    }
+}
+
+macro_rules! serialize_prim {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Content, Error> {
+                Ok(Content::from(value))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ContentSerializer {
+    type Ok = Content;
+    type Error = Error;
+
+    type SerializeSeq = SerializeSeq;
+    type SerializeTuple = SerializeSeq;
+    type SerializeTupleStruct = SerializeSeq;
+    type SerializeTupleVariant = SerializeSeq;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeMap;
+
+    serialize_prim!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_str(&str)
+    );
+
+    fn serialize_char(self, value: char) -> Result<Content, Error> {
+        Ok(Content::String(value.to_string()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Content, Error> {
+        Ok(Content::Bytes(value.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Content, Error> {
+        Ok(Content::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Content, Error> {
+        self.content(value)
+    }
+
+    fn serialize_unit(self) -> Result<Content, Error> {
+        Ok(Content::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Content, Error> {
+        Ok(Content::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Content, Error> {
+        Ok(Content::from(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Content, Error> {
+        self.content(value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Content, Error> {
+        Ok(Self::variant(variant, self.content(value)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq, Error> {
+        Ok(SerializeSeq {
+            serializer: self,
+            variant: None,
+            values: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq, Error> {
+        let mut seq = self.serialize_seq(Some(len))?;
+        seq.variant = Some(variant);
+        Ok(seq)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            serializer: self,
+            variant: None,
+            entries: IndexMap::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeMap, Error> {
+        let mut map = self.serialize_map(Some(len))?;
+        map.variant = Some(variant);
+        Ok(map)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+}
+
+pub(crate) struct SerializeSeq {
+    serializer: ContentSerializer,
+    variant: Option<&'static str>,
+    values: Vec<Content>,
+}
+
+impl SerializeSeq {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.values.push(self.serializer.content(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Content, Error> {
+        let content = Content::Seq(self.values);
+        Ok(match self.variant {
+            Some(variant) => ContentSerializer::variant(variant, content),
+            None => content,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeSeq {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeSeq {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeSeq {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeSeq {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        self.finish()
+    }
+}
+
+pub(crate) struct SerializeMap {
+    serializer: ContentSerializer,
+    variant: Option<&'static str>,
+    entries: IndexMap<String, Content>,
+    /// The key passed to `serialize_key`, waiting for its value.
+    key: Option<String>,
+}
+
+impl SerializeMap {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        let value = self.serializer.content(value)?;
+        self.entries.insert(key, value);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Content, Error> {
+        let content = Content::Map(self.entries);
+        Ok(match self.variant {
+            Some(variant) => ContentSerializer::variant(variant, content),
+            None => content,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(self.serializer.content(key)?.into_key()?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ser::Error::custom("`serialize_value` called before `serialize_key`"))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeMap {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        self.finish()
+    }
+}
@@ -0,0 +1,134 @@
+use alloc::{borrow::Cow, string::ToString, vec, vec::Vec};
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    dec::{Decode, DecodeResult, Decoder, ValueContainer as _},
+    enc::{Encode, EncodeResult, Encoder, ValueContainer as _},
+};
+
+/// A borrowed byte string, encoded with `encode_bytes` rather than as a
+/// sequence of integers.
+///
+/// Decoding only succeeds if the decoder can lend the bytes from its input;
+/// use [`ByteBuf`] otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes<'a>(pub &'a [u8]);
+
+/// An owned byte string, encoded with `encode_bytes` rather than as a sequence
+/// of integers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteBuf(pub Vec<u8>);
+
+impl<'a> Bytes<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl ByteBuf {
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl Deref for Bytes<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl Deref for ByteBuf {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.0
+    }
+}
+
+impl DerefMut for ByteBuf {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+}
+
+impl<'a> From<&'a [u8]> for Bytes<'a> {
+    fn from(value: &'a [u8]) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Vec<u8>> for ByteBuf {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ByteBuf> for Vec<u8> {
+    fn from(value: ByteBuf) -> Self {
+        value.0
+    }
+}
+
+impl Encode for Bytes<'_> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let mut con = encoder.as_value_container();
+        con.encode_bytes(self.0)?;
+        Ok(con.finish())
+    }
+}
+
+impl Encode for ByteBuf {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let mut con = encoder.as_value_container();
+        con.encode_bytes(&self.0)?;
+        Ok(con.finish())
+    }
+}
+
+impl<'de> Decode<'de> for Bytes<'de> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let mut con = decoder.as_value_container()?;
+        match con.decode_bytes()? {
+            Cow::Borrowed(x) => Ok(Bytes(x)),
+            Cow::Owned(_) => Err(con.custom_error(
+                "bytes cannot be borrowed from the input, decode a `ByteBuf` instead".to_string(),
+            )),
+        }
+    }
+}
+
+/// Reads a byte string like [`Bytes`], so it needs the decoder to lend the
+/// bytes too.
+impl<'de> Decode<'de> for &'de [u8] {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        Bytes::decode(decoder).map(|x| x.0)
+    }
+}
+
+impl<'de> Decode<'de> for ByteBuf {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let mut con = decoder.as_value_container()?;
+        Ok(ByteBuf(con.decode_bytes()?.into_owned()))
+    }
+}
@@ -1,7 +1,14 @@
-use std::{
+use alloc::{
     borrow::Cow,
-    fmt::{Debug, Display},
+    boxed::Box,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
 };
+use core::fmt::{Debug, Display, Write as _};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CodingKey<'a> {
@@ -18,6 +25,14 @@ impl<'a> CodingKey<'a> {
             CodingKey::Int(x) => Cow::Owned(format!("{}", x)),
         }
     }
+
+    pub fn into_owned(self) -> CodingKey<'static> {
+        match self {
+            CodingKey::Root => CodingKey::Root,
+            CodingKey::Int(x) => CodingKey::Int(x),
+            CodingKey::String(x) => CodingKey::String(Cow::Owned(x.into_owned())),
+        }
+    }
 }
 
 pub trait ToCodingKey {
@@ -33,12 +48,46 @@ impl ToCodingKey for String {
     }
 }
 
-impl<'a> ToCodingKey for &'a str {
+impl ToCodingKey for &str {
     fn to_coding_key(&self) -> CodingKey<'_> {
         CodingKey::String(Cow::Owned(self.to_string()))
     }
 }
 
+impl ToCodingKey for Cow<'_, str> {
+    fn to_coding_key(&self) -> CodingKey<'_> {
+        CodingKey::String(Cow::Borrowed(self))
+    }
+}
+
+impl ToCodingKey for char {
+    fn to_coding_key(&self) -> CodingKey<'_> {
+        CodingKey::String(Cow::Owned(self.to_string()))
+    }
+}
+
+impl ToCodingKey for bool {
+    fn to_coding_key(&self) -> CodingKey<'_> {
+        CodingKey::String(Cow::Borrowed(if *self { "true" } else { "false" }))
+    }
+}
+
+// Integers are keys of a map rather than indices of a sequence, so they are
+// encoded as strings, matching how `FromCodingKey` reads them back.
+macro_rules! to_coding_key_int {
+    ($($ty:ty),*) => {
+        $(
+            impl ToCodingKey for $ty {
+                fn to_coding_key(&self) -> CodingKey<'_> {
+                    CodingKey::String(Cow::Owned(self.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+to_coding_key_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
 #[cfg(feature = "uuid")]
 impl ToCodingKey for uuid::Uuid {
     fn to_coding_key(&self) -> CodingKey<'_> {
@@ -46,8 +95,94 @@ impl ToCodingKey for uuid::Uuid {
     }
 }
 
+/// The counterpart to [`ToCodingKey`], used to decode map keys into types
+/// other than `String`.
+///
+/// Errors are returned as a message; the caller attaches the coding path.
+pub trait FromCodingKey: Sized {
+    fn from_coding_key(key: &CodingKey<'_>) -> Result<Self, String>;
+}
+
+fn key_str<'k>(key: &'k CodingKey<'_>, expected: &str) -> Result<Cow<'k, str>, String> {
+    match key {
+        CodingKey::Root => Err(invalid_key(key, expected)),
+        CodingKey::Int(x) => Ok(Cow::Owned(x.to_string())),
+        CodingKey::String(x) => Ok(Cow::Borrowed(x)),
+    }
+}
+
+fn invalid_key(key: &CodingKey<'_>, expected: &str) -> String {
+    format!("invalid key `{key}`, expected {expected}")
+}
+
+macro_rules! from_coding_key_str {
+    ($($ty:ty),*) => {
+        $(
+            impl FromCodingKey for $ty {
+                fn from_coding_key(key: &CodingKey<'_>) -> Result<Self, String> {
+                    Ok(key_str(key, "a string")?.into_owned().into())
+                }
+            }
+        )*
+    };
+}
+
+from_coding_key_str!(String, Box<str>, Rc<str>, Arc<str>, Cow<'static, str>);
+
+macro_rules! from_coding_key_int {
+    ($($ty:ty),*) => {
+        $(
+            impl FromCodingKey for $ty {
+                fn from_coding_key(key: &CodingKey<'_>) -> Result<Self, String> {
+                    let expected = concat!("an integer of type ", stringify!($ty));
+                    match key {
+                        CodingKey::Int(x) => {
+                            <$ty>::try_from(*x).map_err(|_| invalid_key(key, expected))
+                        }
+                        _ => key_str(key, expected)?
+                            .parse()
+                            .map_err(|_| invalid_key(key, expected)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_coding_key_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl FromCodingKey for bool {
+    fn from_coding_key(key: &CodingKey<'_>) -> Result<Self, String> {
+        match &*key_str(key, "a bool")? {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(invalid_key(key, "a bool")),
+        }
+    }
+}
+
+impl FromCodingKey for char {
+    fn from_coding_key(key: &CodingKey<'_>) -> Result<Self, String> {
+        let s = key_str(key, "a single character")?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(invalid_key(key, "a single character")),
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl FromCodingKey for uuid::Uuid {
+    fn from_coding_key(key: &CodingKey<'_>) -> Result<Self, String> {
+        key_str(key, "a uuid")?
+            .parse()
+            .map_err(|_| invalid_key(key, "a uuid"))
+    }
+}
+
 impl Display for CodingKey<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             CodingKey::Root => f.write_str("<root>"),
             CodingKey::String(x) => Display::fmt(&x, f),
@@ -56,62 +191,393 @@ impl Display for CodingKey<'_> {
     }
 }
 
+/// The path from the root of the value being encoded or decoded to the current
+/// position.
+///
+/// Paths form a stack. `join` borrows the parent, so descending into a value
+/// allocates nothing, while `join_shared` keeps a share of a copy of the
+/// parent instead, for a path that has to outlive it, such as that of a
+/// container handed back to the caller. Either way a path can be moved across
+/// threads, and it is only copied out in full by `to_path_buf`, when it ends
+/// up in an error.
+#[derive(Clone)]
+pub struct CodingPath<'a> {
+    parent: Option<Parent<'a>>,
+    key: CodingKey<'a>,
+    depth: usize,
+}
+
 #[derive(Clone)]
-pub struct CodingPath<'a>(*const CodingPath<'a>, CodingKey<'a>);
+enum Parent<'a> {
+    Borrowed(&'a CodingPath<'a>),
+    Shared(Arc<CodingPath<'a>>),
+}
 
 impl Debug for CodingPath<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.to_vec().fmt(f)
     }
 }
 
 impl Display for CodingPath<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut v = self.iter().map(|x| x.as_str()).collect::<Vec<_>>();
-        v.pop(); // pop the root element
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut v = self.iter().collect::<Vec<_>>();
         v.reverse();
-        f.write_str(&v.join("."))
+        fmt_dotted(v, f)
     }
 }
 
 impl<'a> CodingPath<'a> {
     pub fn root() -> CodingPath<'a> {
-        CodingPath(std::ptr::null(), CodingKey::Root)
+        CodingPath {
+            parent: None,
+            key: CodingKey::Root,
+            depth: 0,
+        }
     }
 
-    pub fn replace(&'a mut self, item: CodingKey<'a>) {
-        self.1 = item;
+    /// Replaces the last key of the path, keeping its parent.
+    pub fn replace(&mut self, item: CodingKey<'a>) {
+        self.key = item;
     }
 
-    pub fn join(&self, item: CodingKey<'a>) -> CodingPath<'a> {
-        CodingPath(self, item)
+    pub fn join(&'a self, item: CodingKey<'a>) -> CodingPath<'a> {
+        CodingPath {
+            parent: Some(Parent::Borrowed(self)),
+            key: item,
+            depth: self.depth + 1,
+        }
+    }
+
+    /// Like [`join`](Self::join), but the new path holds a copy of this one
+    /// rather than borrowing it, which costs an allocation.
+    pub fn join_shared(&self, item: CodingKey<'a>) -> CodingPath<'a> {
+        CodingPath {
+            parent: Some(Parent::Shared(Arc::new(self.clone()))),
+            key: item,
+            depth: self.depth + 1,
+        }
+    }
+
+    /// The number of keys below the root, i.e. how many containers enclose the
+    /// value at this path.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn key(&self) -> &CodingKey<'a> {
+        &self.key
     }
 
-    pub fn iter(&'a self) -> CodingPathIter<'a> {
-        CodingPathIter { current: self }
+    pub fn parent(&self) -> Option<&CodingPath<'a>> {
+        match self.parent.as_ref()? {
+            Parent::Borrowed(parent) => Some(parent),
+            Parent::Shared(parent) => Some(parent),
+        }
+    }
+
+    pub fn iter(&self) -> CodingPathIter<'_> {
+        CodingPathIter {
+            current: Some(self),
+        }
     }
 
-    pub fn to_vec(&'a self) -> Vec<CodingKey<'_>> {
-        let mut vec = self.iter().cloned().collect::<Vec<_>>();
+    pub fn to_vec(&self) -> Vec<CodingKey<'a>> {
+        let mut vec = vec![];
+        let mut current = Some(self);
+        while let Some(path) = current {
+            vec.push(path.key().clone());
+            current = path.parent();
+        }
         vec.reverse();
         vec
     }
+
+    pub fn to_path_buf(&self) -> CodingPathBuf {
+        let mut keys = self
+            .iter()
+            .filter(|x| !matches!(x, CodingKey::Root))
+            .map(|x| x.clone().into_owned())
+            .collect::<Vec<_>>();
+        keys.reverse();
+        CodingPathBuf(keys)
+    }
 }
 
 pub struct CodingPathIter<'a> {
-    current: *const CodingPath<'a>,
+    current: Option<&'a CodingPath<'a>>,
 }
 
 impl<'a> Iterator for CodingPathIter<'a> {
     type Item = &'a CodingKey<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current.is_null() {
-            return None;
+        let path = self.current?;
+        self.current = path.parent();
+        Some(path.key())
+    }
+}
+
+/// An owned coding path, detached from the encoder or decoder that produced it.
+///
+/// The root is represented by an empty path, so `keys()` only contains the keys
+/// leading from the root to the value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CodingPathBuf(Vec<CodingKey<'static>>);
+
+impl CodingPathBuf {
+    pub const fn root() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn keys(&self) -> &[CodingKey<'static>] {
+        &self.0
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, CodingKey<'static>> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&CodingKey<'static>> {
+        self.0.last()
+    }
+
+    pub fn push(&mut self, key: CodingKey<'_>) {
+        if !matches!(key, CodingKey::Root) {
+            self.0.push(key.into_owned());
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<CodingKey<'static>> {
+        self.0.pop()
+    }
+
+    pub fn join(&self, key: CodingKey<'_>) -> CodingPathBuf {
+        let mut path = self.clone();
+        path.push(key);
+        path
+    }
+
+    /// Displays the path in JSON Pointer notation (RFC 6901), e.g. `/a/0/b~1c`.
+    pub fn json_pointer(&self) -> JsonPointer<'_> {
+        JsonPointer(self)
+    }
+}
+
+impl Display for CodingPathBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_dotted(&self.0, f)
+    }
+}
+
+impl From<&CodingPath<'_>> for CodingPathBuf {
+    fn from(value: &CodingPath<'_>) -> Self {
+        value.to_path_buf()
+    }
+}
+
+impl From<Vec<CodingKey<'static>>> for CodingPathBuf {
+    fn from(value: Vec<CodingKey<'static>>) -> Self {
+        let mut path = CodingPathBuf::root();
+        for key in value {
+            path.push(key);
+        }
+        path
+    }
+}
+
+impl<'a> IntoIterator for &'a CodingPathBuf {
+    type Item = &'a CodingKey<'static>;
+    type IntoIter = core::slice::Iter<'a, CodingKey<'static>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+pub struct JsonPointer<'a>(&'a CodingPathBuf);
+
+impl Display for JsonPointer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for key in self.0.iter() {
+            f.write_str("/")?;
+            match key {
+                CodingKey::Root => {}
+                CodingKey::Int(x) => Display::fmt(x, f)?,
+                CodingKey::String(x) => {
+                    for c in x.chars() {
+                        match c {
+                            '~' => f.write_str("~0")?,
+                            '/' => f.write_str("~1")?,
+                            c => f.write_char(c)?,
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes keys in dotted notation, e.g. `a.b[0]."c.d"`.
+///
+/// Indices are written in brackets and string keys that would be ambiguous are
+/// quoted, so that `a.b` the key and `a` → `b` the nesting can be told apart.
+/// An empty path is written as `<root>`.
+fn fmt_dotted<'a, 'k: 'a>(
+    keys: impl IntoIterator<Item = &'a CodingKey<'k>>,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    let mut is_empty = true;
+    for key in keys {
+        match key {
+            CodingKey::Root => continue,
+            CodingKey::Int(x) => write!(f, "[{x}]")?,
+            CodingKey::String(x) => {
+                if !is_empty {
+                    f.write_char('.')?;
+                }
+                if x.is_empty() || x.contains(['.', '[', ']', '"', '\\']) {
+                    f.write_char('"')?;
+                    for c in x.chars() {
+                        if matches!(c, '"' | '\\') {
+                            f.write_char('\\')?;
+                        }
+                        f.write_char(c)?;
+                    }
+                    f.write_char('"')?;
+                } else {
+                    f.write_str(x)?;
+                }
+            }
+        }
+        is_empty = false;
+    }
+
+    if is_empty {
+        f.write_str("<root>")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_buf_display() {
+        let root = CodingPath::root();
+        let a = root.join(CodingKey::String("a".into()));
+        let b = a.join(CodingKey::Int(0));
+        let c = b.join(CodingKey::String("c.d".into()));
+        let path = c.to_path_buf();
+
+        assert_eq!(path.len(), 3);
+        assert_eq!(path.last(), Some(&CodingKey::String("c.d".into())));
+        assert_eq!(path.to_string(), r#"a[0]."c.d""#);
+        assert_eq!(path.json_pointer().to_string(), "/a/0/c.d");
+        assert_eq!(c.to_string(), path.to_string());
+
+        let nested = CodingPathBuf::from(vec![
+            CodingKey::String("c".into()),
+            CodingKey::String("d".into()),
+        ]);
+        assert_eq!(nested.to_string(), "c.d");
+        assert_ne!(nested, path);
+
+        let mut path = path;
+        path.pop();
+        path.push(CodingKey::Int(9));
+        assert_eq!(path.to_string(), "a[0][9]");
+        assert_eq!(CodingPathBuf::root().to_string(), "<root>");
+        assert_eq!(CodingPathBuf::root().json_pointer().to_string(), "");
+        assert_eq!(
+            CodingPathBuf::from(vec![CodingKey::String("a/b~c".into())])
+                .json_pointer()
+                .to_string(),
+            "/a~1b~0c"
+        );
+    }
+
+    #[test]
+    fn path_is_send_and_shared() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let root = CodingPath::root();
+        let mut item = root
+            .join_shared(CodingKey::String("items".into()))
+            .join_shared(CodingKey::Int(0));
+        assert_send_sync(&item);
+
+        let child = item.join_shared(CodingKey::String("name".into()));
+        item.replace(CodingKey::Int(1));
+
+        let handle = std::thread::spawn(move || child.to_string());
+        assert_eq!(handle.join().unwrap(), "items[0].name");
+
+        let borrowed = item.join(CodingKey::String("name".into()));
+        assert_send_sync(&borrowed);
+        std::thread::scope(|scope| {
+            let handle = scope.spawn(|| borrowed.to_string());
+            assert_eq!(handle.join().unwrap(), "items[1].name");
+        });
+        assert_eq!(item.to_string(), "items[1]");
+        assert_eq!(
+            item.to_vec(),
+            vec![
+                CodingKey::Root,
+                CodingKey::String("items".into()),
+                CodingKey::Int(1)
+            ]
+        );
+        assert_eq!(item.iter().count(), 3);
+    }
+
+    #[test]
+    fn from_coding_key() {
+        let key = |x: &'static str| CodingKey::String(x.into());
+
+        assert_eq!(u8::from_coding_key(&key("42")), Ok(42));
+        assert_eq!(u8::from_coding_key(&CodingKey::Int(7)), Ok(7));
+        assert_eq!(i64::from_coding_key(&key("-3")), Ok(-3));
+        assert_eq!(
+            u8::from_coding_key(&key("300")),
+            Err("invalid key `300`, expected an integer of type u8".to_string())
+        );
+        assert_eq!(bool::from_coding_key(&key("true")), Ok(true));
+        assert!(bool::from_coding_key(&key("yes")).is_err());
+        assert_eq!(char::from_coding_key(&key("x")), Ok('x'));
+        assert!(char::from_coding_key(&key("xy")).is_err());
+        assert_eq!(
+            Arc::<str>::from_coding_key(&CodingKey::Int(1)).as_deref(),
+            Ok("1")
+        );
+        assert!(String::from_coding_key(&CodingKey::Root).is_err());
+    }
+
+    #[test]
+    fn to_coding_key_round_trip() {
+        fn round_trip<T: ToCodingKey + FromCodingKey>(x: &T) -> T {
+            T::from_coding_key(&x.to_coding_key()).unwrap()
         }
 
-        let data = unsafe { &*self.current };
-        self.current = data.0;
-        Some(&data.1)
+        assert_eq!(round_trip(&-12i32), -12);
+        assert_eq!(round_trip(&u128::MAX), u128::MAX);
+        assert_eq!(round_trip(&'k'), 'k');
+        assert!(round_trip(&true));
+        assert_eq!(
+            round_trip(&Cow::<'static, str>::Borrowed("cow")),
+            Cow::<str>::Borrowed("cow")
+        );
+        assert_eq!(4u8.as_str(), "4");
     }
 }
@@ -0,0 +1,311 @@
+//! How the chrono impls represent dates, set per encoder and decoder.
+//!
+//! The strategies apply to `DateTime`, `NaiveDateTime` and `NaiveDate`. Naive
+//! values are taken to be in UTC, and a `NaiveDate` to be its midnight.
+//! `NaiveTime` has no date, so it always uses its own representation.
+//! A `DateTime` read with a format that has no offset is taken to be in UTC.
+
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+};
+use core::fmt::{self, Write as _};
+
+use chrono::{
+    format::ParseErrorKind, DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc,
+};
+
+use crate::{
+    dec::{self, ValueContainer as _},
+    enc::{self, ValueContainer as _},
+};
+
+/// How an encoder has the chrono impls write dates, after Swift's
+/// `JSONEncoder.DateEncodingStrategy`.
+#[derive(Clone, Default)]
+pub enum DateEncodingStrategy {
+    /// Each type's own representation: ISO 8601 text in human-readable
+    /// formats, and a count from the Unix epoch in compact ones.
+    #[default]
+    Default,
+    /// ISO 8601 text, even in compact formats.
+    Iso8601,
+    /// Seconds since the Unix epoch, as a float when there is a fraction.
+    SecondsSinceEpoch,
+    MillisecondsSinceEpoch,
+    MicrosecondsSinceEpoch,
+    /// Text in a chrono format string, such as `"%d/%m/%Y %H:%M"`.
+    Formatted(Cow<'static, str>),
+    /// Text built by the closure.
+    Custom(Arc<FormatDateWith>),
+}
+
+/// How a decoder has the chrono impls read dates, after Swift's
+/// `JSONDecoder.DateDecodingStrategy`. Each variant reads what the
+/// [`DateEncodingStrategy`] of the same name writes.
+#[derive(Clone, Default)]
+pub enum DateDecodingStrategy {
+    #[default]
+    Default,
+    Iso8601,
+    /// Seconds since the Unix epoch, whole or not.
+    SecondsSinceEpoch,
+    MillisecondsSinceEpoch,
+    MicrosecondsSinceEpoch,
+    Formatted(Cow<'static, str>),
+    /// Text parsed by the closure, which returns an error message on failure.
+    Custom(Arc<ParseDateWith>),
+}
+
+/// The closure of [`DateEncodingStrategy::Custom`].
+pub type FormatDateWith = dyn Fn(&DateTime<FixedOffset>) -> String + Send + Sync;
+
+/// The closure of [`DateDecodingStrategy::Custom`].
+pub type ParseDateWith = dyn Fn(&str) -> Result<DateTime<FixedOffset>, String> + Send + Sync;
+
+pub(crate) static DEFAULT_ENCODING: DateEncodingStrategy = DateEncodingStrategy::Default;
+pub(crate) static DEFAULT_DECODING: DateDecodingStrategy = DateDecodingStrategy::Default;
+
+impl fmt::Debug for DateEncodingStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("Default"),
+            Self::Iso8601 => f.write_str("Iso8601"),
+            Self::SecondsSinceEpoch => f.write_str("SecondsSinceEpoch"),
+            Self::MillisecondsSinceEpoch => f.write_str("MillisecondsSinceEpoch"),
+            Self::MicrosecondsSinceEpoch => f.write_str("MicrosecondsSinceEpoch"),
+            Self::Formatted(format) => f.debug_tuple("Formatted").field(format).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl fmt::Debug for DateDecodingStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("Default"),
+            Self::Iso8601 => f.write_str("Iso8601"),
+            Self::SecondsSinceEpoch => f.write_str("SecondsSinceEpoch"),
+            Self::MillisecondsSinceEpoch => f.write_str("MillisecondsSinceEpoch"),
+            Self::MicrosecondsSinceEpoch => f.write_str("MicrosecondsSinceEpoch"),
+            Self::Formatted(format) => f.debug_tuple("Formatted").field(format).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// A chrono type the strategies apply to when encoding.
+pub(crate) trait Date {
+    fn to_date_time(&self) -> DateTime<FixedOffset>;
+    fn iso8601(&self) -> String;
+    fn format_into(&self, out: &mut String, format: &str) -> fmt::Result;
+}
+
+/// A chrono type the strategies apply to when decoding.
+pub(crate) trait ParseDate: Sized {
+    fn from_date_time(value: DateTime<FixedOffset>) -> Self;
+    fn parse_iso8601(s: &str) -> chrono::ParseResult<Self>;
+    fn parse_formatted(s: &str, format: &str) -> chrono::ParseResult<Self>;
+}
+
+impl<Tz: chrono::TimeZone> Date for DateTime<Tz>
+where
+    Tz::Offset: fmt::Display,
+{
+    fn to_date_time(&self) -> DateTime<FixedOffset> {
+        self.fixed_offset()
+    }
+
+    fn iso8601(&self) -> String {
+        self.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+    }
+
+    fn format_into(&self, out: &mut String, format: &str) -> fmt::Result {
+        write!(out, "{}", self.format(format))
+    }
+}
+
+macro_rules! parse_date_time {
+    ($($tz:ty: |$value:ident| $from:expr),*) => {
+        $(
+            impl ParseDate for DateTime<$tz> {
+                fn from_date_time($value: DateTime<FixedOffset>) -> Self {
+                    $from
+                }
+
+                fn parse_iso8601(s: &str) -> chrono::ParseResult<Self> {
+                    DateTime::parse_from_rfc3339(s).map(Self::from_date_time)
+                }
+
+                fn parse_formatted(s: &str, format: &str) -> chrono::ParseResult<Self> {
+                    match DateTime::parse_from_str(s, format) {
+                        Err(e) if e.kind() == ParseErrorKind::NotEnough => {
+                            NaiveDateTime::parse_from_str(s, format)
+                                .map(|v| Self::from_date_time(v.and_utc().fixed_offset()))
+                        }
+                        result => result.map(Self::from_date_time),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+parse_date_time!(
+    Utc: |value| value.to_utc(),
+    FixedOffset: |value| value
+);
+#[cfg(feature = "std")]
+parse_date_time!(chrono::Local: |value| value.with_timezone(&chrono::Local));
+
+impl Date for NaiveDateTime {
+    fn to_date_time(&self) -> DateTime<FixedOffset> {
+        self.and_utc().fixed_offset()
+    }
+
+    fn iso8601(&self) -> String {
+        use chrono::Timelike;
+        if self.nanosecond() != 0 {
+            self.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()
+        } else {
+            self.format("%Y-%m-%dT%H:%M:%S").to_string()
+        }
+    }
+
+    fn format_into(&self, out: &mut String, format: &str) -> fmt::Result {
+        write!(out, "{}", self.format(format))
+    }
+}
+
+impl ParseDate for NaiveDateTime {
+    fn from_date_time(value: DateTime<FixedOffset>) -> Self {
+        value.naive_utc()
+    }
+
+    fn parse_iso8601(s: &str) -> chrono::ParseResult<Self> {
+        // `%.f` takes a fraction of any precision, or none.
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+    }
+
+    fn parse_formatted(s: &str, format: &str) -> chrono::ParseResult<Self> {
+        NaiveDateTime::parse_from_str(s, format)
+    }
+}
+
+impl Date for NaiveDate {
+    fn to_date_time(&self) -> DateTime<FixedOffset> {
+        self.and_time(NaiveTime::MIN).to_date_time()
+    }
+
+    fn iso8601(&self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+
+    fn format_into(&self, out: &mut String, format: &str) -> fmt::Result {
+        write!(out, "{}", self.format(format))
+    }
+}
+
+impl ParseDate for NaiveDate {
+    fn from_date_time(value: DateTime<FixedOffset>) -> Self {
+        value.naive_utc().date()
+    }
+
+    fn parse_iso8601(s: &str) -> chrono::ParseResult<Self> {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+    }
+
+    fn parse_formatted(s: &str, format: &str) -> chrono::ParseResult<Self> {
+        NaiveDate::parse_from_str(s, format)
+    }
+}
+
+/// Encodes `value` into `con` with any strategy but `Default`, which each impl handles
+/// itself.
+pub(crate) fn encode<'e, E, T>(
+    value: &T,
+    strategy: &DateEncodingStrategy,
+    mut con: E::ValueContainer,
+) -> enc::EncodeResult<'e, E>
+where
+    E: enc::Encoder<'e>,
+    T: Date,
+{
+    match strategy {
+        DateEncodingStrategy::Default | DateEncodingStrategy::Iso8601 => {
+            con.encode_str(&value.iso8601())?
+        }
+        DateEncodingStrategy::SecondsSinceEpoch => {
+            let micros = value.to_date_time().timestamp_micros();
+            if micros % 1_000_000 == 0 {
+                con.encode_i64(micros / 1_000_000)?;
+            } else {
+                con.encode_f64(micros as f64 / 1_000_000.0)?;
+            }
+        }
+        DateEncodingStrategy::MillisecondsSinceEpoch => {
+            con.encode_i64(value.to_date_time().timestamp_millis())?
+        }
+        DateEncodingStrategy::MicrosecondsSinceEpoch => {
+            con.encode_i64(value.to_date_time().timestamp_micros())?
+        }
+        DateEncodingStrategy::Formatted(format) => {
+            let mut out = String::new();
+            if value.format_into(&mut out, format).is_err() {
+                return Err(con.custom_error(format!(
+                    "the format `{format}` needs fields this value does not have"
+                )));
+            }
+            con.encode_str(&out)?;
+        }
+        DateEncodingStrategy::Custom(f) => con.encode_str(&f(&value.to_date_time()))?,
+    }
+    Ok(con.finish())
+}
+
+/// Decodes a value from `d` with any strategy but `Default`, which each impl handles
+/// itself.
+pub(crate) fn decode<'de, D, T>(
+    strategy: &DateDecodingStrategy,
+    mut d: D::ValueContainer,
+) -> dec::DecodeResult<'de, T, D>
+where
+    D: dec::Decoder<'de>,
+    T: ParseDate,
+{
+    let micros = match strategy {
+        DateDecodingStrategy::Default | DateDecodingStrategy::Iso8601 => {
+            let s = d.decode_string()?;
+            return T::parse_iso8601(&s).map_err(|e| dec::parse_error(&d, &s, e));
+        }
+        DateDecodingStrategy::Formatted(format) => {
+            let s = d.decode_string()?;
+            return T::parse_formatted(&s, format).map_err(|e| dec::parse_error(&d, &s, e));
+        }
+        DateDecodingStrategy::Custom(f) => {
+            let s = d.decode_string()?;
+            return f(&s)
+                .map(T::from_date_time)
+                .map_err(|message| dec::parse_error(&d, &s, message));
+        }
+        DateDecodingStrategy::SecondsSinceEpoch => {
+            let micros = d.decode_f64()? * 1_000_000.0;
+            // Rounds to the nearest microsecond once truncated, without
+            // `f64::round`, which needs std. The range check rules out NaN.
+            let micros = if micros < 0.0 {
+                micros - 0.5
+            } else {
+                micros + 0.5
+            };
+            (-9.2e18..9.2e18).contains(&micros).then_some(micros as i64)
+        }
+        DateDecodingStrategy::MillisecondsSinceEpoch => d.decode_i64()?.checked_mul(1_000),
+        DateDecodingStrategy::MicrosecondsSinceEpoch => Some(d.decode_i64()?),
+    };
+    micros
+        .and_then(DateTime::from_timestamp_micros)
+        .map(|v| T::from_date_time(v.fixed_offset()))
+        .ok_or_else(|| d.custom_error("timestamp is out of range".to_string()))
+}
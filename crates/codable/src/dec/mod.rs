@@ -1,34 +1,395 @@
-use std::collections::{BTreeMap, HashMap};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque},
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+#[cfg(feature = "indexmap")]
+use core::hash::BuildHasher;
+#[cfg(any(feature = "std", feature = "indexmap"))]
+use core::hash::Hash;
+use core::{
+    any::Any,
+    cell::{Cell, RefCell},
+    cmp::Reverse,
+    marker::PhantomData,
+    num::{Saturating, Wrapping},
+    ops::DerefMut,
+};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
 #[cfg(feature = "indexmap")]
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
+
+use crate::{
+    trace::{self, debug, trace_span},
+    CodingKey, CodingPath, CodingPathBuf, FromCodingKey, ToCodingKey, UserInfo,
+};
+
+pub trait CustomError: Sized {
+    fn custom(coding_path: CodingPathBuf, message: String) -> Self;
 
-use crate::{CodingPath, ToCodingKey};
+    fn missing_key(coding_path: CodingPathBuf, key: &str) -> Self {
+        Self::custom(coding_path, format!("missing key `{key}`"))
+    }
 
-pub trait CustomError {
-    fn custom(coding_path: String, message: String) -> Self;
+    fn invalid_type(coding_path: CodingPathBuf, expected: &str, found: &str) -> Self {
+        Self::custom(
+            coding_path,
+            format!("invalid type: expected {expected}, found {found}"),
+        )
+    }
+
+    fn unknown_variant(
+        coding_path: CodingPathBuf,
+        variant: &str,
+        expected: &'static [&'static str],
+    ) -> Self {
+        Self::custom(
+            coding_path,
+            format!(
+                "unknown variant `{variant}`, expected one of: {}",
+                expected.join(", ")
+            ),
+        )
+    }
+
+    fn invalid_length(coding_path: CodingPathBuf, len: usize, expected: &str) -> Self {
+        Self::custom(
+            coding_path,
+            format!("invalid length {len}, expected {expected}"),
+        )
+    }
+
+    fn out_of_range(coding_path: CodingPathBuf, value: &str, expected: &str) -> Self {
+        Self::custom(
+            coding_path,
+            format!("value {value} is out of range for {expected}"),
+        )
+    }
+
+    fn limit_exceeded(coding_path: CodingPathBuf, limit: Limit, max: usize) -> Self {
+        Self::custom(
+            coding_path,
+            format!("{} exceeds the limit of {max}", limit.as_str()),
+        )
+    }
+
+    /// Attaches the span trace captured where this crate built the error.
+    /// The default drops it; formats whose errors can carry one override this.
+    #[cfg(feature = "tracing-error")]
+    fn with_span_trace(self, span_trace: tracing_error::SpanTrace) -> Self {
+        let _ = span_trace;
+        self
+    }
 }
 
-pub trait Decoder {
+pub trait Decoder<'de> {
     type Value;
     type Error: CustomError;
 
-    type KeyedContainer: KeyedContainer<Decoder = Self, Value = Self::Value, Error = Self::Error>;
-    type ValueContainer: ValueContainer<Decoder = Self, Value = Self::Value, Error = Self::Error>;
-    type SeqContainer: SeqContainer<Decoder = Self, Value = Self::Value, Error = Self::Error>;
+    type KeyedContainer: KeyedContainer<
+        'de,
+        Decoder = Self,
+        Value = Self::Value,
+        Error = Self::Error,
+    >;
+    type ValueContainer: ValueContainer<
+        'de,
+        Decoder = Self,
+        Value = Self::Value,
+        Error = Self::Error,
+    >;
+    type SeqContainer: SeqContainer<'de, Decoder = Self, Value = Self::Value, Error = Self::Error>;
+
+    /// The path from the root to the value this decoder reads, which the
+    /// provided methods report errors against.
+    fn coding_path(&self) -> &CodingPath<'_>;
 
     fn as_container(&mut self) -> Result<Self::KeyedContainer, Self::Error>;
     fn as_value_container(&mut self) -> Result<Self::ValueContainer, Self::Error>;
     fn as_seq_container(&mut self) -> Result<Self::SeqContainer, Self::Error>;
+
+    /// Whether the format is meant to be read by people. Impls with both a
+    /// textual and a compact form, such as `Uuid` and the chrono types, expect
+    /// the compact one when this is false.
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+
+    /// Values supplied by the caller for `Decode` impls to consult, inherited
+    /// by every nested decoder. Formats without a store return an empty one.
+    fn user_info(&self) -> &UserInfo {
+        UserInfo::empty()
+    }
+
+    /// Shorthand for looking a value up in [`user_info`](Self::user_info).
+    fn context<T: Any>(&self) -> Option<&T> {
+        self.user_info().get::<T>()
+    }
+
+    /// Returns the kind of the value about to be decoded, without consuming it.
+    ///
+    /// Only self-describing formats can answer this; the default returns an
+    /// error saying so.
+    fn peek_kind(&mut self) -> Result<ValueKind, Self::Error> {
+        Err(trace::dec_error(Self::Error::custom(
+            self.coding_path().to_path_buf(),
+            "the format is not self-describing, so the kind of a value cannot be inspected"
+                .to_string(),
+        )))
+    }
+
+    /// The sink for recoverable errors when decoding in collect-all-errors
+    /// mode, shared by every nested decoder. `None`, the default, means
+    /// decoding stops at the first error.
+    fn error_collector(&self) -> Option<&ErrorCollector<Self::Error>> {
+        None
+    }
+
+    /// The limits to enforce on the input. The default imposes none.
+    fn limits(&self) -> &Limits {
+        &NO_LIMITS
+    }
+
+    /// How the chrono impls read dates, inherited by every nested decoder.
+    /// The default leaves each type to its own representation.
+    #[cfg(feature = "chrono")]
+    fn date_decoding_strategy(&self) -> &crate::date::DateDecodingStrategy {
+        &crate::date::DEFAULT_DECODING
+    }
+
+    fn collects_errors(&self) -> bool {
+        self.error_collector().is_some()
+    }
+
+    /// Runs `f`, recording its error and returning `None` when collecting
+    /// errors, or passing the error on otherwise.
+    ///
+    /// An error is only recorded if `f` recorded none itself: once a nested
+    /// value has reported its problems, the error it fails with just says that
+    /// it could not be built.
+    fn recover<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, Self::Error>,
+    ) -> Result<Option<T>, Self::Error> {
+        let Some(errors) = self.error_collector() else {
+            return f().map(Some);
+        };
+        let before = errors.len();
+        match f() {
+            Ok(value) => Ok(Some(value)),
+            Err(error) => {
+                if errors.len() == before {
+                    debug!(
+                        coding_path = %self.coding_path(),
+                        "recorded a decode error, carrying on"
+                    );
+                    errors.push(error);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// The error to return from a value some of whose parts failed to decode
+    /// after their errors were recorded with [`recover`](Self::recover).
+    fn recovered_error(&self) -> Self::Error {
+        trace::dec_error(Self::Error::custom(
+            self.coding_path().to_path_buf(),
+            "value could not be decoded".to_string(),
+        ))
+    }
+}
+
+/// Bounds on the size of the input, for decoding data from untrusted sources.
+/// Every limit is off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// How deeply containers may nest, counting the outermost as 1.
+    pub max_depth: Option<usize>,
+    pub max_seq_len: Option<usize>,
+    pub max_map_len: Option<usize>,
+    /// The longest string, in bytes.
+    pub max_string_len: Option<usize>,
+}
+
+static NO_LIMITS: Limits = Limits::none();
+
+impl Limits {
+    pub const fn none() -> Self {
+        Self {
+            max_depth: None,
+            max_seq_len: None,
+            max_map_len: None,
+            max_string_len: None,
+        }
+    }
+
+    pub fn get(&self, limit: Limit) -> Option<usize> {
+        match limit {
+            Limit::Depth => self.max_depth,
+            Limit::SeqLen => self.max_seq_len,
+            Limit::MapLen => self.max_map_len,
+            Limit::StringLen => self.max_string_len,
+        }
+    }
+
+    /// Fails with [`CustomError::limit_exceeded`] if `value` is over `limit`.
+    /// The path is only built on failure.
+    pub fn check<E: CustomError>(
+        &self,
+        limit: Limit,
+        value: usize,
+        coding_path: impl FnOnce() -> CodingPathBuf,
+    ) -> Result<(), E> {
+        match self.get(limit) {
+            Some(max) if value > max => {
+                let coding_path = coding_path();
+                debug!(%coding_path, limit = limit.as_str(), value, max, "limit exceeded");
+                Err(trace::dec_error(E::limit_exceeded(coding_path, limit, max)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a container may be opened at `coding_path`.
+    pub fn check_depth<E: CustomError>(&self, coding_path: &CodingPath<'_>) -> Result<(), E> {
+        self.check(Limit::Depth, coding_path.depth() + 1, || {
+            coding_path.to_path_buf()
+        })
+    }
+}
+
+/// One of the [`Limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    Depth,
+    SeqLen,
+    MapLen,
+    StringLen,
+}
+
+impl Limit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Limit::Depth => "nesting depth",
+            Limit::SeqLen => "sequence length",
+            Limit::MapLen => "map size",
+            Limit::StringLen => "string length",
+        }
+    }
+}
+
+/// The recoverable errors recorded while decoding in collect-all-errors mode.
+///
+/// Clones share the same list, so a format hands a clone to each nested
+/// decoder and reads the whole list back once decoding is done.
+///
+/// Without the `std` feature there is no `Mutex`, so the list is not shared
+/// across threads.
+pub struct ErrorCollector<E>(SharedErrors<E>);
+
+#[cfg(feature = "std")]
+type SharedErrors<E> = Arc<std::sync::Mutex<Vec<E>>>;
+#[cfg(not(feature = "std"))]
+type SharedErrors<E> = Rc<RefCell<Vec<E>>>;
+
+impl<E> ErrorCollector<E> {
+    pub fn new() -> Self {
+        Self(Default::default())
+    }
+
+    pub fn push(&self, error: E) {
+        self.errors().push(error);
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors().is_empty()
+    }
+
+    /// Removes and returns every error recorded so far.
+    pub fn take(&self) -> Vec<E> {
+        core::mem::take(&mut self.errors())
+    }
+
+    #[cfg(feature = "std")]
+    fn errors(&self) -> impl DerefMut<Target = Vec<E>> + '_ {
+        // A panic while pushing cannot leave the list half-written.
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn errors(&self) -> impl DerefMut<Target = Vec<E>> + '_ {
+        self.0.borrow_mut()
+    }
+}
+
+impl<E> Clone for ErrorCollector<E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E> Default for ErrorCollector<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Debug for ErrorCollector<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.errors().iter()).finish()
+    }
 }
 
-pub trait KeyedContainer {
+/// The kind of a value in a self-describing format, as returned by
+/// [`Decoder::peek_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Null,
+    Bool,
+    Int,
+    Float,
+    String,
+    Bytes,
+    Seq,
+    Map,
+}
+
+impl ValueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValueKind::Null => "null",
+            ValueKind::Bool => "bool",
+            ValueKind::Int => "integer",
+            ValueKind::Float => "float",
+            ValueKind::String => "string",
+            ValueKind::Bytes => "bytes",
+            ValueKind::Seq => "sequence",
+            ValueKind::Map => "map",
+        }
+    }
+}
+
+pub trait KeyedContainer<'de> {
     type Error: CustomError;
     type Value;
     type Keys<'a>: Iterator<Item = &'a String>
     where
         Self: 'a;
-    type Decoder: Decoder;
+    type Decoder: Decoder<'de>;
 
     fn coding_path(&self) -> &CodingPath<'_>;
     fn contains(&self, coding_key: &impl ToCodingKey) -> bool;
@@ -47,25 +408,49 @@ pub trait KeyedContainer {
     fn decode_i128(&mut self, key: &impl ToCodingKey) -> Result<i128, Self::Error>;
     fn decode_isize(&mut self, key: &impl ToCodingKey) -> Result<isize, Self::Error>;
     fn decode_string(&mut self, key: &impl ToCodingKey) -> Result<String, Self::Error>;
+
+    /// Decodes a string, borrowing it from the input when the format can lend
+    /// it and falling back to `decode_string` otherwise.
+    fn decode_str(&mut self, key: &impl ToCodingKey) -> Result<Cow<'de, str>, Self::Error> {
+        self.decode_string(key).map(Cow::Owned)
+    }
+
+    /// Decodes a single character. The default decodes a one-character string.
+    fn decode_char(&mut self, key: &impl ToCodingKey) -> Result<char, Self::Error> {
+        let value = self.decode_str(key)?;
+        single_char(&value).map_err(|len| {
+            trace::dec_error(Self::Error::invalid_length(
+                self.coding_path().to_path_buf().join(key.to_coding_key()),
+                len,
+                "a single character",
+            ))
+        })
+    }
+
+    /// Decodes a byte string written with `encode_bytes`, borrowing it from the
+    /// input when the format can lend it.
+    fn decode_bytes(&mut self, key: &impl ToCodingKey) -> Result<Cow<'de, [u8]>, Self::Error> {
+        self.decode::<Vec<u8>>(key).map(Cow::Owned)
+    }
     fn decode_f32(&mut self, key: &impl ToCodingKey) -> Result<f32, Self::Error>;
     fn decode_f64(&mut self, key: &impl ToCodingKey) -> Result<f64, Self::Error>;
     fn decode_bool(&mut self, key: &impl ToCodingKey) -> Result<bool, Self::Error>;
-    fn decode_option<T: Decode>(
+    fn decode_option<T: Decode<'de>>(
         &mut self,
         key: &impl ToCodingKey,
     ) -> Result<Option<T>, Self::Error>;
 
-    fn decode<T: Decode>(&mut self, key: &impl ToCodingKey) -> Result<T, Self::Error>;
+    fn decode<T: Decode<'de>>(&mut self, key: &impl ToCodingKey) -> Result<T, Self::Error>;
 
-    fn nested_container<'a>(
-        &'a mut self,
+    fn nested_container(
+        &mut self,
         key: &impl ToCodingKey,
-    ) -> Result<<Self::Decoder as Decoder>::KeyedContainer, Self::Error>;
+    ) -> Result<<Self::Decoder as Decoder<'de>>::KeyedContainer, Self::Error>;
 
-    fn nested_seq_container<'a>(
-        &'a mut self,
+    fn nested_seq_container(
+        &mut self,
         key: &impl ToCodingKey,
-    ) -> Result<<Self::Decoder as Decoder>::SeqContainer, Self::Error>;
+    ) -> Result<<Self::Decoder as Decoder<'de>>::SeqContainer, Self::Error>;
 
     fn opt_decode_u8(&mut self, key: &impl ToCodingKey) -> Result<Option<u8>, Self::Error> {
         if self.contains(key) {
@@ -174,18 +559,21 @@ pub trait KeyedContainer {
         }
     }
 
-    fn custom_error(&self, message: String) -> <Self as KeyedContainer>::Error {
-        <<Self as KeyedContainer>::Error as CustomError>::custom(
-            self.coding_path().to_string(),
-            message,
+    fn custom_error(&self, message: String) -> <Self as KeyedContainer<'de>>::Error {
+        debug!(coding_path = %self.coding_path(), %message, "decode error");
+        trace::dec_error(
+            <<Self as KeyedContainer<'de>>::Error as CustomError>::custom(
+                self.coding_path().to_path_buf(),
+                message,
+            ),
         )
     }
 }
 
-pub trait ValueContainer {
+pub trait ValueContainer<'de> {
     type Error: CustomError;
     type Value;
-    type Decoder: Decoder;
+    type Decoder: Decoder<'de>;
 
     fn coding_path(&self) -> &CodingPath<'_>;
 
@@ -202,30 +590,93 @@ pub trait ValueContainer {
     fn decode_i128(&mut self) -> Result<i128, Self::Error>;
     fn decode_isize(&mut self) -> Result<isize, Self::Error>;
     fn decode_string(&mut self) -> Result<String, Self::Error>;
+
+    /// Decodes a string, borrowing it from the input when the format can lend
+    /// it and falling back to `decode_string` otherwise.
+    fn decode_str(&mut self) -> Result<Cow<'de, str>, Self::Error> {
+        self.decode_string().map(Cow::Owned)
+    }
+
+    /// Decodes a single character. The default decodes a one-character string.
+    fn decode_char(&mut self) -> Result<char, Self::Error> {
+        let value = self.decode_str()?;
+        single_char(&value).map_err(|len| {
+            trace::dec_error(Self::Error::invalid_length(
+                self.coding_path().to_path_buf(),
+                len,
+                "a single character",
+            ))
+        })
+    }
+
+    /// Decodes a byte string written with `encode_bytes`, borrowing it from the
+    /// input when the format can lend it.
+    fn decode_bytes(&mut self) -> Result<Cow<'de, [u8]>, Self::Error> {
+        self.decode::<Vec<u8>>().map(Cow::Owned)
+    }
     fn decode_f32(&mut self) -> Result<f32, Self::Error>;
     fn decode_f64(&mut self) -> Result<f64, Self::Error>;
+
+    /// Decodes a number as the text the input wrote it with, so that no
+    /// precision is lost to `f64`. The default formats `decode_f64`.
+    fn decode_number_str(&mut self) -> Result<Cow<'de, str>, Self::Error> {
+        self.decode_f64().map(|x| Cow::Owned(x.to_string()))
+    }
     fn decode_bool(&mut self) -> Result<bool, Self::Error>;
     fn decode_null(&mut self) -> Result<(), Self::Error>;
-    fn decode_option<T: Decode>(&mut self) -> Result<Option<T>, Self::Error>;
-    fn decode<T: Decode>(&mut self) -> Result<T, Self::Error>;
+    fn decode_option<T: Decode<'de>>(&mut self) -> Result<Option<T>, Self::Error>;
+    fn decode<T: Decode<'de>>(&mut self) -> Result<T, Self::Error>;
 
-    fn custom_error(&self, message: String) -> <Self as ValueContainer>::Error {
-        <<Self as ValueContainer>::Error as CustomError>::custom(
-            self.coding_path().to_string(),
-            message,
+    fn custom_error(&self, message: String) -> <Self as ValueContainer<'de>>::Error {
+        debug!(coding_path = %self.coding_path(), %message, "decode error");
+        trace::dec_error(
+            <<Self as ValueContainer<'de>>::Error as CustomError>::custom(
+                self.coding_path().to_path_buf(),
+                message,
+            ),
         )
     }
 }
 
-pub trait SeqContainer {
+pub trait SeqContainer<'de> {
     type Error: CustomError;
     type Value;
-    type Decoder: Decoder;
+    type Decoder: Decoder<'de>;
 
+    /// The path of the element at the cursor, i.e. the parent path joined with
+    /// the index of the next element to be decoded.
     fn coding_path(&self) -> &CodingPath<'_>;
-    fn len(&self) -> usize;
     fn cursor_index(&self) -> usize;
 
+    /// Whether every element has been decoded. Streaming formats may need to
+    /// read ahead to answer, hence `&mut self` and the error.
+    fn is_at_end(&mut self) -> Result<bool, Self::Error>;
+
+    /// The exact number of elements, for formats that know it up front.
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether the sequence has no elements, when the format knows its length.
+    fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// An estimate of the number of elements, used to preallocate. Defaults to
+    /// `len`.
+    fn size_hint(&self) -> Option<usize> {
+        self.len()
+    }
+
+    /// Decodes the next element, or returns `None` once the sequence is
+    /// exhausted.
+    fn next_element<T: Decode<'de>>(&mut self) -> Result<Option<T>, Self::Error> {
+        if self.is_at_end()? {
+            return Ok(None);
+        }
+        self.decode().map(Some)
+    }
+
     fn decode_u8(&mut self) -> Result<u8, Self::Error>;
     fn decode_u16(&mut self) -> Result<u16, Self::Error>;
     fn decode_u32(&mut self) -> Result<u32, Self::Error>;
@@ -239,43 +690,92 @@ pub trait SeqContainer {
     fn decode_i128(&mut self) -> Result<i128, Self::Error>;
     fn decode_isize(&mut self) -> Result<isize, Self::Error>;
     fn decode_string(&mut self) -> Result<String, Self::Error>;
+
+    /// Decodes a string, borrowing it from the input when the format can lend
+    /// it and falling back to `decode_string` otherwise.
+    fn decode_str(&mut self) -> Result<Cow<'de, str>, Self::Error> {
+        self.decode_string().map(Cow::Owned)
+    }
+
+    /// Decodes a single character. The default decodes a one-character string.
+    fn decode_char(&mut self) -> Result<char, Self::Error> {
+        // Decoding advances the cursor, so take the element's path first.
+        let coding_path = self.coding_path().to_path_buf();
+        let value = self.decode_str()?;
+        single_char(&value).map_err(|len| {
+            trace::dec_error(Self::Error::invalid_length(
+                coding_path,
+                len,
+                "a single character",
+            ))
+        })
+    }
+
+    /// Decodes a byte string written with `encode_bytes`, borrowing it from the
+    /// input when the format can lend it.
+    fn decode_bytes(&mut self) -> Result<Cow<'de, [u8]>, Self::Error> {
+        self.decode::<Vec<u8>>().map(Cow::Owned)
+    }
     fn decode_f32(&mut self) -> Result<f32, Self::Error>;
     fn decode_f64(&mut self) -> Result<f64, Self::Error>;
     fn decode_bool(&mut self) -> Result<bool, Self::Error>;
-    fn decode_option<T: Decode>(&mut self) -> Result<Option<T>, Self::Error>;
-    fn decode<T: Decode>(&mut self) -> Result<T, Self::Error>;
+    fn decode_option<T: Decode<'de>>(&mut self) -> Result<Option<T>, Self::Error>;
+    /// Decodes the element at the cursor. The cursor moves past the element
+    /// even if it fails to decode, so that decoding can carry on when errors
+    /// are being collected.
+    fn decode<T: Decode<'de>>(&mut self) -> Result<T, Self::Error>;
 
-    fn nested_container<'a>(
-        &'a mut self,
-    ) -> Result<<Self::Decoder as Decoder>::KeyedContainer, Self::Error>;
+    fn nested_container(
+        &mut self,
+    ) -> Result<<Self::Decoder as Decoder<'de>>::KeyedContainer, Self::Error>;
 
-    fn nested_seq_container<'a>(
-        &'a mut self,
-    ) -> Result<<Self::Decoder as Decoder>::SeqContainer, Self::Error>;
+    fn nested_seq_container(
+        &mut self,
+    ) -> Result<<Self::Decoder as Decoder<'de>>::SeqContainer, Self::Error>;
 
-    fn custom_error(&self, message: String) -> <Self as SeqContainer>::Error {
-        <<Self as SeqContainer>::Error as CustomError>::custom(
-            self.coding_path().to_string(),
+    fn custom_error(&self, message: String) -> <Self as SeqContainer<'de>>::Error {
+        debug!(coding_path = %self.coding_path(), %message, "decode error");
+        trace::dec_error(<<Self as SeqContainer<'de>>::Error as CustomError>::custom(
+            self.coding_path().to_path_buf(),
             message,
-        )
+        ))
+    }
+}
+
+/// Returns the only character of `value`, or its length in characters.
+fn single_char(value: &str) -> Result<char, usize> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(value.chars().count()),
     }
 }
 
-pub type DecodeResult<'d, T, D> = Result<T, <D as Decoder>::Error>;
+pub type DecodeResult<'de, T, D> = Result<T, <D as Decoder<'de>>::Error>;
 
-pub trait Decode {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+/// A type that can be decoded from a [`Decoder`].
+///
+/// `'de` is the lifetime of the data being decoded, so implementations may
+/// borrow from it (e.g. `&'de str`) when the decoder is able to lend it.
+pub trait Decode<'de> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
         Self: Sized,
-        D: Decoder + 'd;
+        D: Decoder<'de>;
 }
 
+/// A type that can be decoded without borrowing from the input, for any
+/// lifetime of the input.
+pub trait DecodeOwned: for<'de> Decode<'de> {}
+
+impl<T> DecodeOwned for T where T: for<'de> Decode<'de> {}
+
 macro_rules! decode_prim {
     ($ty:ident, $func:ident) => {
-        impl Decode for $ty {
-            fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+        impl<'de> Decode<'de> for $ty {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
             where
-                D: Decoder,
+                D: Decoder<'de>,
             {
                 let mut con = decoder.as_value_container()?;
                 con.$func()
@@ -299,29 +799,102 @@ decode_prim!(isize, decode_isize);
 decode_prim!(f32, decode_f32);
 decode_prim!(f64, decode_f64);
 decode_prim!(bool, decode_bool);
+decode_prim!(char, decode_char);
 
-impl Decode for String {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+impl<'de> Decode<'de> for () {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
-        D: Decoder,
+        D: Decoder<'de>,
     {
         let mut con = decoder.as_value_container()?;
-        con.decode_string()
+        con.decode_null()
+    }
+}
+
+impl<'de> Decode<'de> for String {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let value = decoder.as_value_container()?.decode_string()?;
+        check_string_len(decoder, &value)?;
+        Ok(value)
+    }
+}
+
+impl<'de> Decode<'de> for Cow<'de, str> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let value = decoder.as_value_container()?.decode_str()?;
+        check_string_len(decoder, &value)?;
+        Ok(value)
+    }
+}
+
+impl<'de> Decode<'de> for &'de str {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let mut con = decoder.as_value_container()?;
+        match con.decode_str()? {
+            Cow::Borrowed(x) => {
+                check_string_len(decoder, x)?;
+                Ok(x)
+            }
+            Cow::Owned(_) => Err(con.custom_error(
+                "string cannot be borrowed from the input, decode a `Cow<str>` or `String` instead"
+                    .to_string(),
+            )),
+        }
     }
 }
 
 macro_rules! decode_map {
-    ($ty:ident) => {
-        impl<V: Decode> Decode for $ty<String, V> {
-            fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+    ($ty:ident $(, $bound:path)* $(; $hasher:ident)?) => {
+        impl<'de, K, V $(, $hasher)?> Decode<'de> for $ty<K, V $(, $hasher)?>
+        where
+            K: FromCodingKey $(+ $bound)*,
+            V: Decode<'de>,
+            $($hasher: BuildHasher + Default,)?
+        {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
             where
-                D: Decoder,
+                D: Decoder<'de>,
             {
-                let mut out = Self::new();
-                let mut con = decoder.as_container()?;
+                let mut out = Self::default();
+                let mut con = keyed_container(decoder)?;
+                decoder.limits().check(Limit::MapLen, con.keys().count(), || {
+                    con.coding_path().to_path_buf()
+                })?;
                 let keys = con.keys().cloned().collect::<Vec<_>>();
+                trace_span!(
+                    "decode_map",
+                    coding_path = %con.coding_path(),
+                    len = keys.len(),
+                    ty = core::any::type_name::<Self>(),
+                );
+                let mut failed = false;
                 for k in keys {
-                    out.insert(k.to_string(), con.decode(&k)?);
+                    let parsed = decoder.recover(|| {
+                        let key = CodingKey::String(Cow::Borrowed(&k));
+                        K::from_coding_key(&key).map_err(|message| {
+                            let coding_path = con.coding_path().to_path_buf().join(key);
+                            debug!(%coding_path, %message, "invalid map key");
+                            trace::dec_error(D::Error::custom(coding_path, message))
+                        })
+                    })?;
+                    match (parsed, decoder.recover(|| con.decode(&k))?) {
+                        (Some(key), Some(value)) => {
+                            out.insert(key, value);
+                        }
+                        _ => failed = true,
+                    }
+                }
+                if failed {
+                    return Err(decoder.recovered_error());
                 }
                 Ok(out)
             }
@@ -329,83 +902,626 @@ macro_rules! decode_map {
     };
 }
 
-decode_map!(HashMap);
-decode_map!(BTreeMap);
+#[cfg(feature = "std")]
+decode_map!(HashMap, Eq, Hash);
+decode_map!(BTreeMap, Ord);
 
+// Any hasher, as `IndexMap` only defaults to `RandomState` with std.
 #[cfg(feature = "indexmap")]
-decode_map!(IndexMap);
+decode_map!(IndexMap, Eq, Hash; S);
+
+/// Hands each element of `con` to `f` until the sequence is exhausted, within
+/// the decoder's sequence length limit. When `decoder` collects errors, a
+/// failed element is recorded and decoding moves on to the next one, with the
+/// sequence as a whole failing at the end.
+fn decode_each<'de, D, T>(
+    decoder: &D,
+    con: &mut D::SeqContainer,
+    mut f: impl FnMut(&D::SeqContainer, T) -> Result<(), D::Error>,
+) -> Result<(), D::Error>
+where
+    D: Decoder<'de>,
+    T: Decode<'de>,
+{
+    trace_span!(
+        "decode_seq",
+        coding_path = %seq_coding_path(con),
+        len = con.len(),
+    );
+    let limits = decoder.limits();
+    let mut failed = false;
+    loop {
+        let cursor_index = con.cursor_index();
+        let next = decoder.recover(|| match con.next_element()? {
+            Some(value) => f(con, value).map(|()| true),
+            None => Ok(false),
+        })?;
+        match next {
+            Some(true) => {}
+            Some(false) => break,
+            // An error that leaves the cursor where it was is in the sequence
+            // itself rather than an element, so there is nothing to move on to.
+            None if con.cursor_index() == cursor_index => {
+                return Err(decoder.recovered_error());
+            }
+            None => failed = true,
+        }
+        // Counted as we go, for formats that cannot say how long a sequence is
+        // up front.
+        limits.check(Limit::SeqLen, con.cursor_index(), || seq_coding_path(con))?;
+    }
+    if failed {
+        return Err(decoder.recovered_error());
+    }
+    Ok(())
+}
 
-impl<T: Decode> Decode for Vec<T> {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+impl<'de, T: Decode<'de>> Decode<'de> for Vec<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
-        D: Decoder,
+        D: Decoder<'de>,
     {
-        let mut con = decoder.as_seq_container()?;
-        let mut out: Vec<T> = vec![];
-        while con.cursor_index() != con.len() {
-            out.push(con.decode()?);
+        let mut con = seq_container(decoder)?;
+        let mut out: Vec<T> = Vec::with_capacity(preallocation(decoder, &con));
+        decode_each(decoder, &mut con, |_, value| {
+            out.push(value);
+            Ok(())
+        })?;
+        Ok(out)
+    }
+}
+
+macro_rules! decode_seq {
+    ($ty:ident, $push:ident $(, $bound:path)*) => {
+        impl<'de, T: Decode<'de> $(+ $bound)*> Decode<'de> for $ty<T> {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                let mut con = seq_container(decoder)?;
+                let mut out = Self::new();
+                decode_each(decoder, &mut con, |_, value| {
+                    out.$push(value);
+                    Ok(())
+                })?;
+                Ok(out)
+            }
         }
+    };
+}
+
+decode_seq!(VecDeque, push_back);
+decode_seq!(LinkedList, push_back);
+decode_seq!(BinaryHeap, push, Ord);
+
+#[cfg(feature = "smallvec")]
+impl<'de, A: smallvec::Array> Decode<'de> for smallvec::SmallVec<A>
+where
+    A::Item: Decode<'de>,
+{
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let mut con = seq_container(decoder)?;
+        let mut out = Self::with_capacity(preallocation(decoder, &con));
+        decode_each(decoder, &mut con, |_, value| {
+            out.push(value);
+            Ok(())
+        })?;
         Ok(out)
     }
 }
 
+/// Sets reject duplicate elements rather than silently dropping them, reporting
+/// the index of the second occurrence.
+macro_rules! decode_set {
+    ($ty:ident $(, $bound:path)* $(; $hasher:ident)?) => {
+        impl<'de, T $(, $hasher)?> Decode<'de> for $ty<T $(, $hasher)?>
+        where
+            T: Decode<'de> $(+ $bound)*,
+            $($hasher: BuildHasher + Default,)?
+        {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                let mut con = seq_container(decoder)?;
+                let mut out = Self::default();
+                decode_each(decoder, &mut con, |con, value| {
+                    if !out.insert(value) {
+                        // The cursor has already moved past the duplicate.
+                        let index = con.cursor_index() - 1;
+                        let coding_path = seq_coding_path(con).join(CodingKey::Int(index));
+                        debug!(%coding_path, "duplicate set element");
+                        return Err(trace::dec_error(D::Error::custom(
+                            coding_path,
+                            "duplicate set element".to_string(),
+                        )));
+                    }
+                    Ok(())
+                })?;
+                Ok(out)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+decode_set!(HashSet, Eq, Hash);
+decode_set!(BTreeSet, Ord);
+
+#[cfg(feature = "indexmap")]
+decode_set!(IndexSet, Eq, Hash; S);
+
+/// The most elements to preallocate for, whatever a sequence claims its length
+/// to be.
+const MAX_PREALLOCATION: usize = 4096;
+
+/// The capacity to reserve for the elements of `con`: its size hint, capped by
+/// the decoder's length limit and [`MAX_PREALLOCATION`] so that a hint from
+/// untrusted input cannot make decoding allocate before anything is checked.
+fn preallocation<'de, D: Decoder<'de>>(decoder: &D, con: &D::SeqContainer) -> usize {
+    let max = decoder.limits().max_seq_len.unwrap_or(usize::MAX);
+    con.size_hint().unwrap_or(0).min(max).min(MAX_PREALLOCATION)
+}
+
+/// Opens `decoder` as a keyed container within its depth limit. Formats may
+/// check the limit themselves as well; the impls here go through this so
+/// that formats which do not are covered too.
+pub(crate) fn keyed_container<'de, D: Decoder<'de>>(
+    decoder: &mut D,
+) -> Result<D::KeyedContainer, D::Error> {
+    decoder.limits().check_depth(decoder.coding_path())?;
+    decoder.as_container()
+}
+
+/// Opens `decoder` as a sequence container within its depth limit, like
+/// [`keyed_container`].
+fn seq_container<'de, D: Decoder<'de>>(decoder: &mut D) -> Result<D::SeqContainer, D::Error> {
+    decoder.limits().check_depth(decoder.coding_path())?;
+    decoder.as_seq_container()
+}
+
+/// Fails if a decoded string is over the decoder's length limit, for formats
+/// that do not check it as they read.
+fn check_string_len<'de, D: Decoder<'de>>(decoder: &D, value: &str) -> Result<(), D::Error> {
+    decoder.limits().check(Limit::StringLen, value.len(), || {
+        decoder.coding_path().to_path_buf()
+    })
+}
+
+/// The path of the sequence itself, rather than of the element at its cursor.
+fn seq_coding_path<'de, S: SeqContainer<'de>>(con: &S) -> CodingPathBuf {
+    let path = con.coding_path();
+    path.parent().unwrap_or(path).to_path_buf()
+}
+
+/// Fails early when the format knows the sequence has the wrong length.
+fn check_len<'de, S: SeqContainer<'de>>(con: &S, n: usize, expected: &str) -> Result<(), S::Error> {
+    match con.len() {
+        Some(len) if len != n => Err(trace::dec_error(S::Error::invalid_length(
+            seq_coding_path(con),
+            len,
+            expected,
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Decodes the next of a fixed number of elements, failing if the sequence
+/// ends first.
+fn next_fixed<'de, S, T>(con: &mut S, expected: &str) -> Result<T, S::Error>
+where
+    S: SeqContainer<'de>,
+    T: Decode<'de>,
+{
+    let index = con.cursor_index();
+    match con.next_element()? {
+        Some(value) => Ok(value),
+        None => Err(trace::dec_error(S::Error::invalid_length(
+            seq_coding_path(con),
+            index,
+            expected,
+        ))),
+    }
+}
+
+/// Fails if a fixed-length sequence has elements left over.
+fn expect_end<'de, S: SeqContainer<'de>>(
+    con: &mut S,
+    n: usize,
+    expected: &str,
+) -> Result<(), S::Error> {
+    if con.is_at_end()? {
+        return Ok(());
+    }
+    let len = con.len().unwrap_or(n + 1);
+    Err(trace::dec_error(S::Error::invalid_length(
+        seq_coding_path(con),
+        len,
+        expected,
+    )))
+}
+
+impl<'de, T: Decode<'de>, const N: usize> Decode<'de> for [T; N] {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let mut con = seq_container(decoder)?;
+        let expected = format!("an array of length {N}");
+        check_len(&con, N, &expected)?;
+        let mut out: Vec<T> = Vec::with_capacity(N);
+        for _ in 0..N {
+            out.push(next_fixed(&mut con, &expected)?);
+        }
+        expect_end(&mut con, N, &expected)?;
+        match out.try_into() {
+            Ok(out) => Ok(out),
+            Err(_) => unreachable!("exactly N elements were decoded"),
+        }
+    }
+}
+
+macro_rules! decode_tuple {
+    ($len:literal => $($name:ident)+) => {
+        impl<'de, $($name: Decode<'de>),+> Decode<'de> for ($($name,)+) {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                let mut con = seq_container(decoder)?;
+                let expected = concat!("a tuple of size ", $len);
+                check_len(&con, $len, expected)?;
+                let out = ($(next_fixed::<_, $name>(&mut con, expected)?,)+);
+                expect_end(&mut con, $len, expected)?;
+                Ok(out)
+            }
+        }
+    };
+}
+
+decode_tuple!(1 => T0);
+decode_tuple!(2 => T0 T1);
+decode_tuple!(3 => T0 T1 T2);
+decode_tuple!(4 => T0 T1 T2 T3);
+decode_tuple!(5 => T0 T1 T2 T3 T4);
+decode_tuple!(6 => T0 T1 T2 T3 T4 T5);
+decode_tuple!(7 => T0 T1 T2 T3 T4 T5 T6);
+decode_tuple!(8 => T0 T1 T2 T3 T4 T5 T6 T7);
+decode_tuple!(9 => T0 T1 T2 T3 T4 T5 T6 T7 T8);
+decode_tuple!(10 => T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+decode_tuple!(11 => T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+decode_tuple!(12 => T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+decode_tuple!(13 => T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
+decode_tuple!(14 => T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13);
+decode_tuple!(15 => T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14);
+decode_tuple!(16 => T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+
+macro_rules! decode_pointer {
+    ($ty:ident) => {
+        impl<'de, T: Decode<'de>> Decode<'de> for $ty<T> {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                Ok($ty::new(T::decode(decoder)?))
+            }
+        }
+
+        impl<'de> Decode<'de> for $ty<str> {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                Ok(String::decode(decoder)?.into())
+            }
+        }
+
+        impl<'de, T: Decode<'de>> Decode<'de> for $ty<[T]> {
+            fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+            where
+                D: Decoder<'de>,
+            {
+                Ok(Vec::<T>::decode(decoder)?.into())
+            }
+        }
+    };
+}
+
+decode_pointer!(Box);
+decode_pointer!(Rc);
+decode_pointer!(Arc);
+
+impl<'de, T: Decode<'de> + Copy> Decode<'de> for Cell<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        Ok(Cell::new(T::decode(decoder)?))
+    }
+}
+
+impl<'de, T: Decode<'de>> Decode<'de> for RefCell<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        Ok(RefCell::new(T::decode(decoder)?))
+    }
+}
+
+impl<'de, T: Decode<'de>> Decode<'de> for Reverse<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        Ok(Reverse(T::decode(decoder)?))
+    }
+}
+
+impl<'de, T: Decode<'de>> Decode<'de> for Wrapping<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        Ok(Wrapping(T::decode(decoder)?))
+    }
+}
+
+impl<'de, T: Decode<'de>> Decode<'de> for Saturating<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        Ok(Saturating(T::decode(decoder)?))
+    }
+}
+
+impl<'de, T: ?Sized> Decode<'de> for PhantomData<T> {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let mut con = decoder.as_value_container()?;
+        con.decode_null()?;
+        Ok(PhantomData)
+    }
+}
+
+/// The error for a string that could not be parsed into the value it holds.
+#[cfg(any(feature = "uuid", feature = "chrono"))]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn parse_error<'de, C: ValueContainer<'de>>(
+    con: &C,
+    input: &str,
+    error: impl core::fmt::Display,
+) -> C::Error {
+    debug!(
+        coding_path = %con.coding_path(),
+        input,
+        "could not parse a string"
+    );
+    con.custom_error(error.to_string())
+}
+
 #[cfg(feature = "uuid")]
-impl Decode for uuid::Uuid {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+impl<'de> Decode<'de> for uuid::Uuid {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
         Self: Sized,
-        D: Decoder,
+        D: Decoder<'de>,
+    {
+        let human_readable = decoder.is_human_readable();
+        let mut d = decoder.as_value_container()?;
+        if !human_readable {
+            let bytes = d.decode_bytes()?;
+            return uuid::Uuid::from_slice(&bytes).map_err(|e| d.custom_error(e.to_string()));
+        }
+        let s = d.decode_string()?;
+        uuid::Uuid::parse_str(&s).map_err(|e| parse_error(&d, &s, e))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'de> Decode<'de> for chrono::NaiveDate {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
     {
+        use crate::date::{DateDecodingStrategy, ParseDate};
+        let human_readable = decoder.is_human_readable();
         let mut d = decoder.as_value_container()?;
+        let strategy = decoder.date_decoding_strategy();
+        if !matches!(strategy, DateDecodingStrategy::Default) {
+            return crate::date::decode::<D, _>(strategy, d);
+        }
+        if !human_readable {
+            let days = d.decode_i32()?;
+            return days
+                .checked_add(crate::UNIX_EPOCH_DAYS_FROM_CE)
+                .and_then(chrono::NaiveDate::from_num_days_from_ce_opt)
+                .ok_or_else(|| {
+                    d.custom_error(format!("date {days} days from the epoch is out of range"))
+                });
+        }
         let s = d.decode_string()?;
-        Ok(uuid::Uuid::parse_str(&s)
-            .map_err(|e| D::Error::custom(d.coding_path().to_string(), e.to_string()))?)
+        Self::parse_iso8601(&s).map_err(|e| parse_error(&d, &s, e))
     }
 }
 
 #[cfg(feature = "chrono")]
-impl Decode for chrono::NaiveDate {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+impl<'de> Decode<'de> for chrono::NaiveDateTime {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
-        D: Decoder,
+        D: Decoder<'de>,
     {
+        use crate::date::{DateDecodingStrategy, ParseDate};
+        let human_readable = decoder.is_human_readable();
         let mut d = decoder.as_value_container()?;
+        let strategy = decoder.date_decoding_strategy();
+        if !matches!(strategy, DateDecodingStrategy::Default) {
+            return crate::date::decode::<D, _>(strategy, d);
+        }
+        if !human_readable {
+            let micros = d.decode_i64()?;
+            return chrono::DateTime::from_timestamp_micros(micros)
+                .map(|v| v.naive_utc())
+                .ok_or_else(|| d.custom_error(format!("timestamp {micros} is out of range")));
+        }
         let s = d.decode_string()?;
-        Ok(chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d")
-            .map_err(|e| D::Error::custom(d.coding_path().to_string(), e.to_string()))?)
+        Self::parse_iso8601(&s).map_err(|e| parse_error(&d, &s, e))
     }
 }
 
 #[cfg(feature = "chrono")]
-impl Decode for chrono::NaiveDateTime {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+impl<'de> Decode<'de> for chrono::NaiveTime {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
-        D: Decoder,
+        D: Decoder<'de>,
     {
+        let human_readable = decoder.is_human_readable();
         let mut d = decoder.as_value_container()?;
+        if !human_readable {
+            let micros = d.decode_u64()?;
+            let secs = u32::try_from(micros / 1_000_000).ok();
+            let nanos = (micros % 1_000_000) as u32 * 1_000;
+            return secs
+                .and_then(|secs| chrono::NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos))
+                .ok_or_else(|| {
+                    d.custom_error(format!(
+                        "time {micros} microseconds from midnight is out of range"
+                    ))
+                });
+        }
         let s = d.decode_string()?;
-        if let Ok(v) = chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S%.6f") {
-            return Ok(v);
+        chrono::NaiveTime::parse_from_str(&s, "%H:%M:%S%.f").map_err(|e| parse_error(&d, &s, e))
+    }
+}
+
+#[cfg(feature = "chrono")]
+macro_rules! decode_date_time {
+    ($($tz:ty),*) => {
+        $(
+            impl<'de> Decode<'de> for chrono::DateTime<$tz> {
+                fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+                where
+                    D: Decoder<'de>,
+                {
+                    use crate::date::{DateDecodingStrategy, ParseDate};
+                    let human_readable = decoder.is_human_readable();
+                    let mut d = decoder.as_value_container()?;
+                    let strategy = decoder.date_decoding_strategy();
+                    if !matches!(strategy, DateDecodingStrategy::Default) {
+                        return crate::date::decode::<D, _>(strategy, d);
+                    }
+                    if !human_readable {
+                        let micros = d.decode_i64()?;
+                        return chrono::DateTime::from_timestamp_micros(micros)
+                            .map(|v| Self::from_date_time(v.fixed_offset()))
+                            .ok_or_else(|| {
+                                d.custom_error(format!("timestamp {micros} is out of range"))
+                            });
+                    }
+                    let s = d.decode_string()?;
+                    Self::parse_iso8601(&s).map_err(|e| parse_error(&d, &s, e))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "chrono")]
+decode_date_time!(chrono::Utc, chrono::FixedOffset);
+#[cfg(all(feature = "chrono", feature = "std"))]
+decode_date_time!(chrono::Local);
+
+/// Reads what `Display` writes: `P0D`, or `PT` and a number of seconds with a
+/// fraction of any precision, all after a `-` when negative.
+#[cfg(feature = "chrono")]
+fn parse_time_delta(s: &str) -> Option<chrono::TimeDelta> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let s = s.strip_prefix('P')?;
+    let delta = if s == "0D" {
+        chrono::TimeDelta::zero()
+    } else {
+        let s = s.strip_prefix('T')?.strip_suffix('S')?;
+        let (secs, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if secs.is_empty() || !all_digits(secs) || fraction.len() > 9 || !all_digits(fraction) {
+            return None;
         }
-        Ok(
-            chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S")
-                .map_err(|e| D::Error::custom(d.coding_path().to_string(), e.to_string()))?,
-        )
+        let nanos = fraction
+            .bytes()
+            .chain(core::iter::repeat(b'0'))
+            .take(9)
+            .fold(0, |n, b| n * 10 + u32::from(b - b'0'));
+        chrono::TimeDelta::new(secs.parse().ok()?, nanos)?
+    };
+    Some(if negative { -delta } else { delta })
+}
+
+/// In compact formats, a `TimeDelta` is a number of nanoseconds, which covers
+/// about 292 years either way.
+#[cfg(feature = "chrono")]
+impl<'de> Decode<'de> for chrono::TimeDelta {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let human_readable = decoder.is_human_readable();
+        let mut d = decoder.as_value_container()?;
+        if !human_readable {
+            return Ok(chrono::TimeDelta::nanoseconds(d.decode_i64()?));
+        }
+        let s = d.decode_string()?;
+        parse_time_delta(&s).ok_or_else(|| {
+            parse_error(
+                &d,
+                &s,
+                format_args!("`{s}` is not an ISO 8601 duration in seconds"),
+            )
+        })
     }
 }
 
 #[cfg(feature = "chrono")]
-impl Decode for chrono::NaiveTime {
-    fn decode<'d, D>(decoder: &mut D) -> DecodeResult<'d, Self, D>
+impl<'de> Decode<'de> for chrono::Weekday {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
     where
-        D: Decoder,
+        D: Decoder<'de>,
     {
+        let human_readable = decoder.is_human_readable();
         let mut d = decoder.as_value_container()?;
+        if !human_readable {
+            let days = d.decode_u8()?;
+            return chrono::Weekday::try_from(days).map_err(|_| {
+                d.custom_error(format!("weekday {days} days from Monday is out of range"))
+            });
+        }
         let s = d.decode_string()?;
-        if let Ok(v) = chrono::NaiveTime::parse_from_str(&s, "%H:%M:%S%.6f") {
-            return Ok(v);
+        s.parse().map_err(|e| parse_error(&d, &s, e))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'de> Decode<'de> for chrono::Month {
+    fn decode<D>(decoder: &mut D) -> DecodeResult<'de, Self, D>
+    where
+        D: Decoder<'de>,
+    {
+        let human_readable = decoder.is_human_readable();
+        let mut d = decoder.as_value_container()?;
+        if !human_readable {
+            let month = d.decode_u8()?;
+            return chrono::Month::try_from(month)
+                .map_err(|_| d.custom_error(format!("month {month} is out of range")));
         }
-        Ok(chrono::NaiveTime::parse_from_str(&s, "%H:%M:%S")
-            .map_err(|e| D::Error::custom(d.coding_path().to_string(), e.to_string()))?)
+        let s = d.decode_string()?;
+        s.parse().map_err(|e| parse_error(&d, &s, e))
     }
 }
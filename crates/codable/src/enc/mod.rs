@@ -1,16 +1,48 @@
-use std::{
-    borrow::Cow,
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque},
+    rc::Rc,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    any::Any,
+    cell::{Cell, RefCell},
+    cmp::Reverse,
     fmt::Debug,
+    marker::PhantomData,
+    num::{Saturating, Wrapping},
 };
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
 #[cfg(feature = "indexmap")]
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
+
+use crate::{
+    trace::{self, debug},
+    CodingPath, CodingPathBuf, ToCodingKey, UserInfo,
+};
 
-use crate::{CodingPath, ToCodingKey};
+pub trait CustomError {
+    fn custom(coding_path: CodingPathBuf, message: String) -> Self;
+
+    /// Attaches the span trace captured where this crate built the error.
+    /// The default drops it; formats whose errors can carry one override this.
+    #[cfg(feature = "tracing-error")]
+    fn with_span_trace(self, span_trace: tracing_error::SpanTrace) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = span_trace;
+        self
+    }
+}
 
 pub trait KeyedContainer {
-    type Error;
+    type Error: CustomError;
     type Value;
     type Encoder<'a>: Encoder<'a>
     where
@@ -38,6 +70,18 @@ pub trait KeyedContainer {
     fn encode_f32(&mut self, value: f32, key: &impl ToCodingKey) -> Result<(), Self::Error>;
     fn encode_f64(&mut self, value: f64, key: &impl ToCodingKey) -> Result<(), Self::Error>;
     fn encode_bool(&mut self, value: bool, key: &impl ToCodingKey) -> Result<(), Self::Error>;
+
+    /// Encodes a single character. The default encodes it as a one-character
+    /// string.
+    fn encode_char(&mut self, value: char, key: &impl ToCodingKey) -> Result<(), Self::Error> {
+        self.encode_str(value.encode_utf8(&mut [0; 4]) as &str, key)
+    }
+
+    /// Encodes a byte string. Binary formats should write the bytes as-is; the
+    /// default encodes them as a sequence of integers.
+    fn encode_bytes(&mut self, value: &[u8], key: &impl ToCodingKey) -> Result<(), Self::Error> {
+        self.encode(&value, key)
+    }
     fn encode_option<T: Encode>(
         &mut self,
         value: Option<&T>,
@@ -48,12 +92,12 @@ pub trait KeyedContainer {
     fn nested_container<'a>(
         &'a mut self,
         key: &'a impl ToCodingKey,
-    ) -> Result<<Self::Encoder<'a> as Encoder<'_>>::KeyedContainer, Self::Error>;
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::KeyedContainer, Self::Error>;
 
     fn nested_seq_container<'a>(
         &'a mut self,
         key: &'a impl ToCodingKey,
-    ) -> Result<<Self::Encoder<'a> as Encoder<'_>>::SeqContainer, Self::Error>;
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::SeqContainer, Self::Error>;
 
     fn opt_encode_u8(
         &mut self,
@@ -222,11 +266,19 @@ pub trait KeyedContainer {
         }
     }
 
+    fn custom_error(&self, message: String) -> <Self as KeyedContainer>::Error {
+        debug!(coding_path = %self.coding_path(), %message, "encode error");
+        trace::enc_error(<<Self as KeyedContainer>::Error as CustomError>::custom(
+            self.coding_path().to_path_buf(),
+            message,
+        ))
+    }
+
     fn finish(self) -> Self::Value;
 }
 
 pub trait ValueContainer {
-    type Error;
+    type Error: CustomError;
     type Value;
     type Encoder<'a>: Encoder<'a>;
 
@@ -248,20 +300,42 @@ pub trait ValueContainer {
     fn encode_f32(&mut self, value: f32) -> Result<(), Self::Error>;
     fn encode_f64(&mut self, value: f64) -> Result<(), Self::Error>;
     fn encode_bool(&mut self, value: bool) -> Result<(), Self::Error>;
+
+    /// Encodes a single character. The default encodes it as a one-character
+    /// string.
+    fn encode_char(&mut self, value: char) -> Result<(), Self::Error> {
+        self.encode_str(value.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Encodes a byte string. Binary formats should write the bytes as-is; the
+    /// default encodes them as a sequence of integers.
+    fn encode_bytes(&mut self, value: &[u8]) -> Result<(), Self::Error> {
+        self.encode(&value)
+    }
     fn encode_null(&mut self) -> Result<(), Self::Error>;
     fn encode_option<T: Encode>(&mut self, value: Option<&T>) -> Result<(), Self::Error>;
     fn encode<T: Encode>(&mut self, value: &T) -> Result<(), Self::Error>;
 
+    fn custom_error(&self, message: String) -> <Self as ValueContainer>::Error {
+        debug!(coding_path = %self.coding_path(), %message, "encode error");
+        trace::enc_error(<<Self as ValueContainer>::Error as CustomError>::custom(
+            self.coding_path().to_path_buf(),
+            message,
+        ))
+    }
+
     fn finish(self) -> Self::Value;
 }
 
 pub trait SeqContainer {
-    type Error;
+    type Error: CustomError;
     type Value;
     type Encoder<'a>: Encoder<'a>
     where
         Self: 'a;
 
+    /// The path of the next element, i.e. the parent path joined with the
+    /// number of elements encoded so far.
     fn coding_path(&self) -> &CodingPath<'_>;
 
     fn encode_u8(&mut self, value: u8) -> Result<(), Self::Error>;
@@ -280,17 +354,37 @@ pub trait SeqContainer {
     fn encode_f32(&mut self, value: f32) -> Result<(), Self::Error>;
     fn encode_f64(&mut self, value: f64) -> Result<(), Self::Error>;
     fn encode_bool(&mut self, value: bool) -> Result<(), Self::Error>;
+
+    /// Encodes a single character. The default encodes it as a one-character
+    /// string.
+    fn encode_char(&mut self, value: char) -> Result<(), Self::Error> {
+        self.encode_str(value.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Encodes a byte string. Binary formats should write the bytes as-is; the
+    /// default encodes them as a sequence of integers.
+    fn encode_bytes(&mut self, value: &[u8]) -> Result<(), Self::Error> {
+        self.encode(&value)
+    }
     fn encode_null(&mut self) -> Result<(), Self::Error>;
     fn encode_option<T: Encode>(&mut self, value: Option<&T>) -> Result<(), Self::Error>;
     fn encode<T: Encode>(&mut self, value: &T) -> Result<(), Self::Error>;
 
     fn nested_container<'a>(
         &'a mut self,
-    ) -> Result<<Self::Encoder<'a> as Encoder<'_>>::KeyedContainer, Self::Error>;
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::KeyedContainer, Self::Error>;
 
     fn nested_seq_container<'a>(
         &'a mut self,
-    ) -> Result<<Self::Encoder<'a> as Encoder<'_>>::SeqContainer, Self::Error>;
+    ) -> Result<<Self::Encoder<'a> as Encoder<'a>>::SeqContainer, Self::Error>;
+
+    fn custom_error(&self, message: String) -> <Self as SeqContainer>::Error {
+        debug!(coding_path = %self.coding_path(), %message, "encode error");
+        trace::enc_error(<<Self as SeqContainer>::Error as CustomError>::custom(
+            self.coding_path().to_path_buf(),
+            message,
+        ))
+    }
 
     fn finish(self) -> Self::Value;
 }
@@ -333,6 +427,29 @@ encode_prim!(isize, encode_isize);
 encode_prim!(f32, encode_f32);
 encode_prim!(f64, encode_f64);
 encode_prim!(bool, encode_bool);
+encode_prim!(char, encode_char);
+
+impl Encode for () {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let mut con = encoder.as_value_container();
+        con.encode_null()?;
+        Ok(con.finish())
+    }
+}
+
+impl Encode for str {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let mut con = encoder.as_value_container();
+        con.encode_str(self)?;
+        Ok(con.finish())
+    }
+}
 
 impl Encode for &str {
     fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
@@ -362,7 +479,7 @@ impl Encode for String {
         E: Encoder<'e>,
     {
         let mut con = encoder.as_value_container();
-        con.encode_str(&self)?;
+        con.encode_str(self)?;
         Ok(con.finish())
     }
 }
@@ -373,14 +490,14 @@ impl<T: Encode + ToOwned> Encode for Cow<'_, T> {
         E: Encoder<'e>,
     {
         let mut con = encoder.as_value_container();
-        con.encode(&*self)?;
+        con.encode(&**self)?;
         Ok(con.finish())
     }
 }
 
 macro_rules! encode_map {
-    ($ty:ident) => {
-        impl<K: ToCodingKey, V: Encode> Encode for $ty<K, V> {
+    ($ty:ident $(; $hasher:ident)?) => {
+        impl<K: ToCodingKey, V: Encode $(, $hasher)?> Encode for $ty<K, V $(, $hasher)?> {
             fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
             where
                 E: Encoder<'e>,
@@ -393,7 +510,7 @@ macro_rules! encode_map {
             }
         }
 
-        impl<K: ToCodingKey, V: Encode> Encode for &$ty<K, V> {
+        impl<K: ToCodingKey, V: Encode $(, $hasher)?> Encode for &$ty<K, V $(, $hasher)?> {
             fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
             where
                 E: Encoder<'e>,
@@ -408,11 +525,12 @@ macro_rules! encode_map {
     };
 }
 
+#[cfg(feature = "std")]
 encode_map!(HashMap);
 encode_map!(BTreeMap);
 
 #[cfg(feature = "indexmap")]
-encode_map!(IndexMap);
+encode_map!(IndexMap; S);
 
 #[cfg(feature = "smallvec")]
 impl<A: smallvec::Array> Encode for smallvec::SmallVec<A>
@@ -437,12 +555,21 @@ impl Encode for uuid::Uuid {
     where
         E: Encoder<'e>,
     {
+        let human_readable = encoder.is_human_readable();
         let mut con = encoder.as_value_container();
-        con.encode_str(&self.to_string())?;
+        if human_readable {
+            con.encode_str(&self.to_string())?;
+        } else {
+            con.encode_bytes(self.as_bytes())?;
+        }
         Ok(con.finish())
     }
 }
 
+/// In compact formats, date-times are microseconds since the Unix epoch, dates
+/// are days since it, and times are microseconds since midnight. Compact output
+/// is therefore truncated to the microsecond, while the text of a `DateTime`
+/// keeps every nanosecond.
 #[cfg(feature = "chrono")]
 impl<Tz: chrono::TimeZone> Encode for chrono::DateTime<Tz>
 where
@@ -452,8 +579,18 @@ where
     where
         E: Encoder<'e>,
     {
+        use crate::date::{Date, DateEncodingStrategy};
+        let human_readable = encoder.is_human_readable();
         let mut con = encoder.as_value_container();
-        con.encode_str(&self.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))?;
+        let strategy = encoder.date_encoding_strategy();
+        if !matches!(strategy, DateEncodingStrategy::Default) {
+            return crate::date::encode::<E, _>(self, strategy, con);
+        }
+        if human_readable {
+            con.encode_str(&self.iso8601())?;
+        } else {
+            con.encode_i64(self.timestamp_micros())?;
+        }
         Ok(con.finish())
     }
 }
@@ -464,8 +601,19 @@ impl Encode for chrono::NaiveDate {
     where
         E: Encoder<'e>,
     {
+        use crate::date::{Date, DateEncodingStrategy};
+        use chrono::Datelike;
+        let human_readable = encoder.is_human_readable();
         let mut con = encoder.as_value_container();
-        con.encode_str(&self.format("%Y-%m-%d").to_string())?;
+        let strategy = encoder.date_encoding_strategy();
+        if !matches!(strategy, DateEncodingStrategy::Default) {
+            return crate::date::encode::<E, _>(self, strategy, con);
+        }
+        if human_readable {
+            con.encode_str(&self.iso8601())?;
+        } else {
+            con.encode_i32(self.num_days_from_ce() - crate::UNIX_EPOCH_DAYS_FROM_CE)?;
+        }
         Ok(con.finish())
     }
 }
@@ -476,12 +624,17 @@ impl Encode for chrono::NaiveDateTime {
     where
         E: Encoder<'e>,
     {
-        use chrono::Timelike;
+        use crate::date::{Date, DateEncodingStrategy};
+        let human_readable = encoder.is_human_readable();
         let mut con = encoder.as_value_container();
-        if self.nanosecond() != 0 {
-            con.encode_str(&self.format("%Y-%m-%dT%H:%M:%S%.6f").to_string())?;
+        let strategy = encoder.date_encoding_strategy();
+        if !matches!(strategy, DateEncodingStrategy::Default) {
+            return crate::date::encode::<E, _>(self, strategy, con);
+        }
+        if human_readable {
+            con.encode_str(&self.iso8601())?;
         } else {
-            con.encode_str(&self.format("%Y-%m-%dT%H:%M:%S").to_string())?;
+            con.encode_i64(self.and_utc().timestamp_micros())?;
         }
         Ok(con.finish())
     }
@@ -494,8 +647,13 @@ impl Encode for chrono::NaiveTime {
         E: Encoder<'e>,
     {
         use chrono::Timelike;
+        let human_readable = encoder.is_human_readable();
         let mut con = encoder.as_value_container();
-        if self.nanosecond() != 0 {
+        if !human_readable {
+            let micros = u64::from(self.num_seconds_from_midnight()) * 1_000_000
+                + u64::from(self.nanosecond() / 1_000);
+            con.encode_u64(micros)?;
+        } else if self.nanosecond() != 0 {
             con.encode_str(&self.format("%H:%M:%S%.6f").to_string())?;
         } else {
             con.encode_str(&self.format("%H:%M:%S").to_string())?;
@@ -504,6 +662,67 @@ impl Encode for chrono::NaiveTime {
     }
 }
 
+/// In human-readable formats, an ISO 8601 duration in seconds, such as
+/// `PT90.5S`. In compact ones, a number of nanoseconds, which fails past about
+/// 292 years either way.
+#[cfg(feature = "chrono")]
+impl Encode for chrono::TimeDelta {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let human_readable = encoder.is_human_readable();
+        let mut con = encoder.as_value_container();
+        if human_readable {
+            con.encode_str(&self.to_string())?;
+        } else {
+            let Some(nanos) = self.num_nanoseconds() else {
+                return Err(con.custom_error(alloc::format!(
+                    "duration {self} is too long to count in nanoseconds"
+                )));
+            };
+            con.encode_i64(nanos)?;
+        }
+        Ok(con.finish())
+    }
+}
+
+/// Chrono's abbreviated name, or the days from Monday in compact formats.
+#[cfg(feature = "chrono")]
+impl Encode for chrono::Weekday {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let human_readable = encoder.is_human_readable();
+        let mut con = encoder.as_value_container();
+        if human_readable {
+            con.encode_str(&self.to_string())?;
+        } else {
+            con.encode_u8(self.num_days_from_monday() as u8)?;
+        }
+        Ok(con.finish())
+    }
+}
+
+/// The full English name, or the month's number in compact formats.
+#[cfg(feature = "chrono")]
+impl Encode for chrono::Month {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let human_readable = encoder.is_human_readable();
+        let mut con = encoder.as_value_container();
+        if human_readable {
+            con.encode_str(self.name())?;
+        } else {
+            con.encode_u8(self.number_from_month() as u8)?;
+        }
+        Ok(con.finish())
+    }
+}
+
 impl<T: Encode> Encode for &Vec<T> {
     fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
     where
@@ -543,6 +762,7 @@ impl<T: Encode> Encode for &[T] {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: Encode> Encode for HashSet<T> {
     fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
     where
@@ -556,6 +776,7 @@ impl<T: Encode> Encode for HashSet<T> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: Encode> Encode for &HashSet<T> {
     fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
     where
@@ -595,9 +816,169 @@ impl<T: Encode> Encode for &BTreeSet<T> {
     }
 }
 
+macro_rules! encode_seq {
+    ($ty:ident $(; $hasher:ident)?) => {
+        impl<T: Encode $(, $hasher)?> Encode for $ty<T $(, $hasher)?> {
+            fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+            where
+                E: Encoder<'e>,
+            {
+                let mut con = encoder.as_seq_container();
+                for v in self.iter() {
+                    con.encode(v)?;
+                }
+                Ok(con.finish())
+            }
+        }
+    };
+}
+
+encode_seq!(VecDeque);
+encode_seq!(LinkedList);
+encode_seq!(BinaryHeap);
+
+#[cfg(feature = "indexmap")]
+encode_seq!(IndexSet; S);
+
+impl<T: Encode> Encode for [T] {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let mut con = encoder.as_seq_container();
+        for v in self.iter() {
+            con.encode(v)?;
+        }
+        Ok(con.finish())
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let mut con = encoder.as_seq_container();
+        for v in self.iter() {
+            con.encode(v)?;
+        }
+        Ok(con.finish())
+    }
+}
+
+macro_rules! encode_tuple {
+    ($($n:tt $name:ident)+) => {
+        impl<$($name: Encode),+> Encode for ($($name,)+) {
+            fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+            where
+                E: Encoder<'e>,
+            {
+                let mut con = encoder.as_seq_container();
+                $(con.encode(&self.$n)?;)+
+                Ok(con.finish())
+            }
+        }
+    };
+}
+
+encode_tuple!(0 T0);
+encode_tuple!(0 T0 1 T1);
+encode_tuple!(0 T0 1 T1 2 T2);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14);
+encode_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15);
+
+macro_rules! encode_pointer {
+    ($ty:ident) => {
+        impl<T: Encode + ?Sized> Encode for $ty<T> {
+            fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+            where
+                E: Encoder<'e>,
+            {
+                (**self).encode(encoder)
+            }
+        }
+    };
+}
+
+encode_pointer!(Box);
+encode_pointer!(Rc);
+encode_pointer!(Arc);
+
+impl<T: Encode + Copy> Encode for Cell<T> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        self.get().encode(encoder)
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for RefCell<T> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        match self.try_borrow() {
+            Ok(value) => value.encode(encoder),
+            Err(_) => Err(encoder
+                .as_value_container()
+                .custom_error("value is already mutably borrowed".to_string())),
+        }
+    }
+}
+
+impl<T: Encode> Encode for Reverse<T> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        self.0.encode(encoder)
+    }
+}
+
+impl<T: Encode> Encode for Wrapping<T> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        self.0.encode(encoder)
+    }
+}
+
+impl<T: Encode> Encode for Saturating<T> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        self.0.encode(encoder)
+    }
+}
+
+impl<T: ?Sized> Encode for PhantomData<T> {
+    fn encode<'e, E>(&self, encoder: &mut E) -> EncodeResult<'e, E>
+    where
+        E: Encoder<'e>,
+    {
+        let mut con = encoder.as_value_container();
+        con.encode_null()?;
+        Ok(con.finish())
+    }
+}
+
 pub trait Encoder<'a>: 'a {
     type Value;
-    type Error: Debug + 'static;
+    type Error: CustomError + Debug + 'static;
 
     type KeyedContainer: KeyedContainer<
         Encoder<'a> = Self,
@@ -622,4 +1003,29 @@ pub trait Encoder<'a>: 'a {
     fn as_container(&mut self) -> Self::KeyedContainer;
     fn as_value_container(&mut self) -> Self::ValueContainer;
     fn as_seq_container(&mut self) -> Self::SeqContainer;
+
+    /// Whether the format is meant to be read by people. Impls with both a
+    /// textual and a compact form, such as `Uuid` and the chrono types, pick
+    /// the compact one when this is false.
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+
+    /// Values supplied by the caller for `Encode` impls to consult, inherited
+    /// by every nested encoder. Formats without a store return an empty one.
+    fn user_info(&self) -> &UserInfo {
+        UserInfo::empty()
+    }
+
+    /// Shorthand for looking a value up in [`user_info`](Self::user_info).
+    fn context<T: Any>(&self) -> Option<&T> {
+        self.user_info().get::<T>()
+    }
+
+    /// How the chrono impls write dates, inherited by every nested encoder.
+    /// The default leaves each type to its own representation.
+    #[cfg(feature = "chrono")]
+    fn date_encoding_strategy(&self) -> &crate::date::DateEncodingStrategy {
+        &crate::date::DEFAULT_ENCODING
+    }
 }
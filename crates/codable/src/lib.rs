@@ -1,21 +1,55 @@
 #![deny(rust_2018_idioms)]
+#![no_std]
 
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod bytes;
 mod coding_path;
+#[cfg(feature = "chrono")]
+pub mod date;
 pub mod dec;
 pub mod enc;
+pub mod registry;
+mod trace;
+mod user_info;
 
 #[cfg(feature = "derive")]
-pub use codable_derive::{Decode, Encode};
+pub use codable_derive::{Decode, Encode, ToCodingKey};
+
+pub use bytes::{ByteBuf, Bytes};
+pub use coding_path::{
+    CodingKey, CodingPath, CodingPathBuf, CodingPathIter, FromCodingKey, JsonPointer, ToCodingKey,
+};
+pub use user_info::UserInfo;
+
+/// Items used by the derive macros, which cannot name `alloc` themselves in a
+/// `no_std` crate. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{borrow::Cow, boxed::Box};
+}
 
-pub use coding_path::{CodingKey, CodingPath, CodingPathIter, ToCodingKey};
+/// `NaiveDate::num_days_from_ce` for 1970-01-01, the zero of compact dates.
+#[cfg(feature = "chrono")]
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
 
-#[cfg(all(test, feature = "derive"))]
+#[cfg(all(test, feature = "derive", feature = "chrono", feature = "uuid"))]
 mod tests {
+    extern crate std;
+
     use super::*;
     use chrono::{DateTime, NaiveDate, Utc};
-    use std::collections::BTreeMap;
+    use codable_json::Value;
+    use std::{
+        collections::BTreeMap,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
 
-    #[derive(Debug, Encode)]
+    #[derive(Debug, PartialEq, Encode, Decode)]
     #[codable(rename = "kebab-case", tag("type", "LeEnum"), tag("second", 42))]
     enum Enum {
         A,
@@ -26,7 +60,7 @@ mod tests {
         AnotherOne,
     }
 
-    #[derive(Debug, Encode, Decode)]
+    #[derive(Debug, PartialEq, Encode, Decode)]
     #[codable(rename = "kebab-case", tag("type", "blep"), tag("type2", "blep2"))]
     struct Something {
         a: u8,
@@ -42,7 +76,7 @@ mod tests {
         k: f32,
         l: f64,
         m: bool,
-        // n: char,
+        n: char,
         o: String,
         p: Vec<u8>,
         q_r_s: Option<u8>,
@@ -54,11 +88,10 @@ mod tests {
         tt: NaiveDate,
     }
 
-    #[test]
-    fn blep() {
+    fn something() -> Something {
         let mut um = BTreeMap::new();
         um.insert(uuid::Uuid::default(), 42);
-        let x = Something {
+        Something {
             a: Default::default(),
             b: Default::default(),
             c: Default::default(),
@@ -72,18 +105,65 @@ mod tests {
             k: 1234.56,
             l: 1234.56,
             m: Default::default(),
-            // n: Default::default(),
+            n: Default::default(),
             o: Default::default(),
-            p: Default::default(),
+            p: vec![1, 2],
             q_r_s: Default::default(),
             le_enum: Enum::AnotherOne,
             le_enums: vec![Enum::AnotherOne, Enum::A],
             u: uuid::Uuid::default(),
             um,
-            t: Utc::now(),
-            tt: NaiveDate::from_ymd(2020, 1, 1),
+            t: DateTime::from_timestamp(1_600_000_000, 123_456_789).unwrap(),
+            tt: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn blep() {
+        let Value::Object(x) = codable_json::to_value(&something()).unwrap() else {
+            panic!("expected an object");
+        };
+        let string = |x: &str| Value::String(x.into());
+        let number = |x: &str| Value::Number(x.into());
+        let le_enum = |x: &str| {
+            Value::Object(
+                [
+                    ("type".to_string(), string("LeEnum")),
+                    ("second".to_string(), number("42")),
+                    ("value".to_string(), string(x)),
+                ]
+                .into_iter()
+                .collect(),
+            )
         };
-        let x = codable_json::to_value(&x).unwrap();
-        println!("{:?}", x);
+
+        assert_eq!(x["type"], string("blep"));
+        assert_eq!(x["type2"], string("blep2"));
+        assert_eq!(x["e"], number("0"));
+        assert_eq!(x["k"], number("1234.56"));
+        assert_eq!(x["p"], Value::Array(vec![number("1"), number("2")]));
+        assert_eq!(x["q-r-s"], Value::Null);
+        assert_eq!(x["le-enum"], le_enum("test"));
+        assert_eq!(
+            x["le-enums"],
+            Value::Array(vec![le_enum("test"), le_enum("a")])
+        );
+        assert_eq!(x["u"], string("00000000-0000-0000-0000-000000000000"));
+        assert_eq!(x["t"], string("2020-09-13T12:26:40.123456789Z"));
+        assert_eq!(x["tt"], string("2020-01-01"));
+        assert_eq!(
+            x.keys().skip(2).map(String::as_str).collect::<Vec<_>>(),
+            [
+                "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p",
+                "q-r-s", "le-enum", "le-enums", "u", "um", "t", "tt"
+            ]
+        );
+    }
+
+    #[test]
+    fn something_round_trip() {
+        let x = something();
+        let value = codable_json::to_value(&x).unwrap();
+        assert_eq!(codable_json::from_value::<Something>(&value).unwrap(), x);
     }
 }
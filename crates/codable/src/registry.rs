@@ -0,0 +1,203 @@
+//! Encoding and decoding trait objects through a registry of concrete types.
+//!
+//! [`registry!`](macro@crate::registry) implements `Encode` and `Decode` for
+//! `Box<dyn Trait>`, given the types that may sit behind it and a tag for each.
+//! A boxed value is written as a map holding its tag under [`TYPE_KEY`] and the
+//! value itself under [`VALUE_KEY`]; decoding reads the tag back and decodes
+//! the registered type. The trait must have `Any` as a supertrait, so that the
+//! encoder can find out which registered type it has been given.
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use core::any::Any;
+//!
+//! use codable::{Decode, Encode};
+//!
+//! trait PluginConfig: Any {}
+//!
+//! #[derive(Encode, Decode)]
+//! struct HttpConfig {
+//!     url: String,
+//! }
+//!
+//! #[derive(Encode, Decode)]
+//! struct FileConfig {
+//!     path: String,
+//! }
+//!
+//! impl PluginConfig for HttpConfig {}
+//! impl PluginConfig for FileConfig {}
+//!
+//! codable::registry! {
+//!     dyn PluginConfig {
+//!         HttpConfig => "http",
+//!         FileConfig => "file",
+//!     }
+//! }
+//!
+//! let configs: Vec<Box<dyn PluginConfig>> = vec![Box::new(FileConfig {
+//!     path: "/etc/plugins".into(),
+//! })];
+//! let value = codable_json::to_value(&configs).unwrap();
+//! let decoded = codable_json::from_value::<Vec<Box<dyn PluginConfig>>>(&value).unwrap();
+//! let any: &dyn Any = &*decoded[0];
+//! assert_eq!(any.downcast_ref::<FileConfig>().unwrap().path, "/etc/plugins");
+//! # }
+//! ```
+//!
+//! The registry is closed: every type for a trait is listed in the one
+//! invocation, which has to live in the crate that defines the trait or the
+//! boxed type's impls would be foreign. Other crates cannot add types to it,
+//! and there can be only one registry per trait. Registering types at runtime
+//! would need `Encode` and `Decode`, which are generic over the format, to be
+//! erased first. Tags must be unique within a
+//! registry, which is checked at compile time.
+//!
+//! The functions here are what the macro expands to.
+
+use alloc::format;
+
+use crate::{
+    dec::{self, CustomError as _, DecodeResult, Decoder, KeyedContainer as _},
+    enc::{Encode, EncodeResult, Encoder, KeyedContainer as _, ValueContainer as _},
+    ToCodingKey as _,
+};
+
+pub const TYPE_KEY: &str = "type";
+pub const VALUE_KEY: &str = "value";
+
+/// Encodes `value` alongside its registered `tag`.
+pub fn encode_tagged<'e, E, T>(encoder: &mut E, tag: &str, value: &T) -> EncodeResult<'e, E>
+where
+    E: Encoder<'e>,
+    T: Encode,
+{
+    let mut con = encoder.as_container();
+    con.encode(&tag, &TYPE_KEY)?;
+    con.encode(value, &VALUE_KEY)?;
+    Ok(con.finish())
+}
+
+/// The error for a value whose type is missing from the registry for `name`.
+pub fn unregistered<'e, E: Encoder<'e>>(encoder: &mut E, name: &str) -> EncodeResult<'e, E> {
+    let con = encoder.as_value_container();
+    Err(con.custom_error(format!("type is not registered for `{name}`")))
+}
+
+/// Reads the tag and hands it to `decode`, which decodes the registered type or
+/// returns `None` if there is none under that tag.
+pub fn decode_tagged<'de, D, T>(
+    decoder: &mut D,
+    tags: &'static [&'static str],
+    decode: impl FnOnce(&str, &mut D::KeyedContainer) -> Option<Result<T, D::Error>>,
+) -> DecodeResult<'de, T, D>
+where
+    D: Decoder<'de>,
+{
+    let mut con = dec::keyed_container(decoder)?;
+    let tag = con.decode::<alloc::borrow::Cow<'de, str>>(&TYPE_KEY)?;
+    match decode(&tag, &mut con) {
+        Some(result) => result,
+        None => Err(D::Error::unknown_variant(
+            con.coding_path()
+                .to_path_buf()
+                .join(TYPE_KEY.to_coding_key()),
+            &tag,
+            tags,
+        )),
+    }
+}
+
+/// Whether any two of `tags` are equal, evaluated at compile time by
+/// [`registry!`](macro@crate::registry).
+#[doc(hidden)]
+pub const fn has_duplicate(tags: &[&str]) -> bool {
+    let mut i = 0;
+    while i < tags.len() {
+        let mut j = i + 1;
+        while j < tags.len() {
+            if str_eq(tags[i], tags[j]) {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Implements `Encode` and `Decode` for `Box<dyn Trait>` over a fixed set of
+/// tagged types. See the [module docs](mod@crate::registry).
+///
+/// Reusing a tag is a compile error:
+///
+/// ```compile_fail
+/// trait Number: core::any::Any {}
+/// impl Number for u8 {}
+/// impl Number for u16 {}
+///
+/// codable::registry! {
+///     dyn Number {
+///         u8 => "int",
+///         u16 => "int",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! registry {
+    ($object:ty { $($ty:ty => $tag:literal),* $(,)? }) => {
+        const _: () = ::core::assert!(
+            !$crate::registry::has_duplicate(&[$($tag),*]),
+            "a tag is registered more than once in `registry!`",
+        );
+
+        impl $crate::enc::Encode for $crate::__private::Box<$object> {
+            fn encode<'e, E>(&self, encoder: &mut E) -> $crate::enc::EncodeResult<'e, E>
+            where
+                E: $crate::enc::Encoder<'e>,
+            {
+                let value: &dyn ::core::any::Any = &**self;
+                $(
+                    if let Some(value) = value.downcast_ref::<$ty>() {
+                        return $crate::registry::encode_tagged(encoder, $tag, value);
+                    }
+                )*
+                $crate::registry::unregistered(encoder, stringify!($object))
+            }
+        }
+
+        impl<'de> $crate::dec::Decode<'de> for $crate::__private::Box<$object> {
+            fn decode<D>(decoder: &mut D) -> $crate::dec::DecodeResult<'de, Self, D>
+            where
+                D: $crate::dec::Decoder<'de>,
+            {
+                use $crate::dec::KeyedContainer as _;
+
+                $crate::registry::decode_tagged(decoder, &[$($tag),*], |tag, con| match tag {
+                    $(
+                        $tag => Some(
+                            con.decode::<$ty>(&$crate::registry::VALUE_KEY)
+                                .map(|value| $crate::__private::Box::new(value) as Self),
+                        ),
+                    )*
+                    _ => None,
+                })
+            }
+        }
+    };
+}
@@ -0,0 +1,41 @@
+//! Hooks for the `tracing` and `tracing-error` features, which compile to
+//! nothing without them.
+
+use crate::{dec, enc};
+
+/// `tracing::debug!`, when the `tracing` feature is on.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Enters a trace-level span until the end of the enclosing block, when the
+/// `tracing` feature is on.
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!($($arg)*).entered();
+    };
+}
+
+pub(crate) use {debug, trace_span};
+
+/// Passes a decoding error built by this crate to
+/// [`with_span_trace`](dec::CustomError::with_span_trace).
+#[inline]
+pub(crate) fn dec_error<E: dec::CustomError>(error: E) -> E {
+    #[cfg(feature = "tracing-error")]
+    let error = error.with_span_trace(tracing_error::SpanTrace::capture());
+    error
+}
+
+/// Passes an encoding error built by this crate to
+/// [`with_span_trace`](enc::CustomError::with_span_trace).
+#[inline]
+pub(crate) fn enc_error<E: enc::CustomError>(error: E) -> E {
+    #[cfg(feature = "tracing-error")]
+    let error = error.with_span_trace(tracing_error::SpanTrace::capture());
+    error
+}
@@ -0,0 +1,101 @@
+use alloc::{collections::BTreeMap, sync::Arc};
+use core::{
+    any::{Any, TypeId},
+    fmt::{self, Debug},
+};
+
+/// Request-scoped values made available to `Encode` and `Decode` impls, keyed
+/// by type.
+///
+/// An encoder or decoder hands its `UserInfo` down to every nested encoder or
+/// decoder it creates, so a value inserted at the top is visible throughout
+/// the tree. Wrap settings in a newtype to keep them from colliding with
+/// another library's.
+#[derive(Clone, Default)]
+pub struct UserInfo(BTreeMap<TypeId, Arc<dyn Any + Send + Sync>>);
+
+static EMPTY: UserInfo = UserInfo::new();
+
+impl UserInfo {
+    pub const fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// A shared empty store, for formats that do not carry one.
+    pub fn empty() -> &'static Self {
+        &EMPTY
+    }
+
+    /// Stores `value`, returning the previous value of the same type, if any.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<Arc<T>> {
+        self.0
+            .insert(TypeId::of::<T>(), Arc::new(value))
+            .and_then(|old| old.downcast().ok())
+    }
+
+    /// Builder-style [`insert`](Self::insert).
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.0
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<Arc<T>> {
+        self.0
+            .remove(&TypeId::of::<T>())
+            .and_then(|old| old.downcast().ok())
+    }
+
+    pub fn contains<T: Any>(&self) -> bool {
+        self.0.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Debug for UserInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserInfo")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct ApiVersion(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct Locale(&'static str);
+
+    #[test]
+    fn typed_values() {
+        let mut info = UserInfo::new().with(ApiVersion(2)).with(Locale("en-AU"));
+        assert_eq!(info.len(), 2);
+        assert_eq!(info.get::<ApiVersion>(), Some(&ApiVersion(2)));
+        assert_eq!(info.get::<Locale>(), Some(&Locale("en-AU")));
+        assert_eq!(info.get::<u32>(), None);
+
+        let old = info.insert(ApiVersion(3)).unwrap();
+        assert_eq!(*old, ApiVersion(2));
+        assert_eq!(info.get::<ApiVersion>(), Some(&ApiVersion(3)));
+
+        assert!(info.remove::<Locale>().is_some());
+        assert!(!info.contains::<Locale>());
+        assert!(UserInfo::empty().is_empty());
+    }
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "derive")]
+
+use std::collections::BTreeMap;
+
+use codable::{Encode, ToCodingKey};
+use codable_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, ToCodingKey)]
+#[codable(rename = "snake-case")]
+enum Region {
+    NorthAmerica,
+    #[codable(rename = "eu")]
+    Europe,
+}
+
+#[test]
+fn derive_to_coding_key() {
+    assert_eq!(Region::NorthAmerica.as_str(), "north_america");
+    assert_eq!(Region::Europe.as_str(), "eu");
+
+    let regions = BTreeMap::from([(Region::Europe, 1u8), (Region::NorthAmerica, 2)]);
+    let Value::Object(map) = codable_json::to_value(&regions).unwrap() else {
+        panic!("expected an object");
+    };
+    let entries = map
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            ("north_america", Value::Number("2".into())),
+            ("eu", Value::Number("1".into())),
+        ]
+    );
+}